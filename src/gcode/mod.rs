@@ -1,7 +1,7 @@
 mod binop;
 pub mod expression;
 use crate::NUM_AXES;
-use alloc::string::String;
+use alloc::{string::String, vec::Vec};
 pub use binop::*;
 use expression::{Expression, Param};
 
@@ -11,17 +11,29 @@ macro_rules! impl_parse_node {
         $(impl ParseNode for $ty {})+
     };
 }
-impl_parse_node!(Command, Gcode, Mcode, Scode, Tcode);
+impl_parse_node!(Command, Gcode, Mcode, Fcode, Scode, Tcode);
 
+// words are kept inline rather than boxed, to avoid allocating for every parsed command
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, PartialEq, Clone)]
 pub enum Command {
+    /// `( comment )`
     Comment(String),
+    /// `; comment` to the end of the line
+    LineComment(String),
     Assign(Param, Expression),
     G(Gcode),
     M(Mcode),
     O(Ocode),
+    F(Fcode),
     S(Scode),
     T(Tcode),
+    /// axis words without a motion code, repeating the active motion mode
+    Axes(Axes),
+    /// NURBS control point words without a `G5.2`, e.g. `X2 Y0 P1`
+    ControlPoint(NurbsPoint),
+    /// `%` program start / end marker
+    ProgramDelimiter,
 }
 
 impl Command {
@@ -30,42 +42,229 @@ impl Command {
     }
 }
 
+/// A single line of gcode, made up of every word on that line
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct Block {
+    /// leading `/`, the block is skipped when the block delete switch is on
+    pub block_delete: bool,
+    /// `N123` line number
+    pub line_number: Option<u32>,
+    pub commands: Vec<Command>,
+}
+
+impl Block {
+    pub fn new(commands: impl Into<Vec<Command>>) -> Self {
+        Self {
+            commands: commands.into(),
+            ..Default::default()
+        }
+    }
+    pub fn block_delete(self, block_delete: bool) -> Self {
+        Self {
+            block_delete,
+            ..self
+        }
+    }
+    pub fn line_number(self, line_number: u32) -> Self {
+        Self {
+            line_number: Some(line_number),
+            ..self
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
+#[allow(non_camel_case_types)]
 pub enum Gcode {
     G0(Option<Axes>),
     G1(Axes),
+    /// clockwise arc
+    G2(Arc),
+    /// counter-clockwise arc
+    G3(Arc),
+    /// dwell for `P`
+    G4(Option<Expression>),
+    /// cubic B-spline in the XY plane
+    G5(Spline),
+    /// quadratic B-spline in the XY plane
+    G5_1(Spline),
+    /// start a NURBS curve in the XY plane, or add a control point to it
+    G5_2(NurbsPoint),
+    /// end a NURBS curve
+    G5_3,
+    /// lathe diameter mode, X words are diameters
+    G7,
+    /// lathe radius mode, X words are radii
+    G8,
+    /// set coordinate system offsets or tool data, `L` selects how
+    G10(SetOffsets),
+    /// XY plane
+    G17,
+    /// UV plane
+    G17_1,
+    /// XZ plane
+    G18,
+    /// UW plane
+    G18_1,
+    /// YZ plane
+    G19,
+    /// VW plane
+    G19_1,
     /// inch units
     G20,
     /// mm units
     G21,
-    /// machine coordinates
+    /// rapid to the `G28` home position, through the optional intermediate point
+    G28(Option<Axes>),
+    /// store the current position as the `G28` home position
+    G28_1,
+    /// rapid to the `G30` home position, through the optional intermediate point
+    G30(Option<Axes>),
+    /// store the current position as the `G30` home position
+    G30_1,
+    /// spindle synchronized motion, `K` is the distance per spindle revolution
+    G33(SyncedMotion),
+    /// rigid tapping, in to the end point and back out with the spindle reversed
+    G33_1(SyncedMotion),
+    /// probe toward the workpiece, error if the probe does not trip
+    G38_2(Axes),
+    /// probe toward the workpiece
+    G38_3(Axes),
+    /// probe away from the workpiece, error if the probe does not trip
+    G38_4(Axes),
+    /// probe away from the workpiece
+    G38_5(Axes),
+    /// cutter compensation off
+    G40,
+    /// cutter compensation left of the path, `D` is a tool number
+    G41(Option<Expression>),
+    /// cutter compensation left of the path, `D` is the tool diameter
+    G41_1(Option<Expression>),
+    /// cutter compensation right of the path, `D` is a tool number
+    G42(Option<Expression>),
+    /// cutter compensation right of the path, `D` is the tool diameter
+    G42_1(Option<Expression>),
+    /// tool length offset of the `H` tool, the current tool without `H`
+    G43(Option<Expression>),
+    /// tool length offset given by the axis words
+    G43_1(Axes),
+    /// add the offset of the `H` tool or the axis words to the tool length offset
+    G43_2(ToolOffset),
+    /// cancel the tool length offset
+    G49,
+    /// exact path mode
+    G61,
+    /// exact stop mode
+    G61_1,
+    /// blend corners, within the `P` tolerance if given
+    G64(Blending),
+    /// drilling cycle with chip breaking
+    G73(CannedCycle),
+    /// left-hand tapping cycle
+    G74(CannedCycle),
+    /// multi-pass threading cycle
+    G76(Threading),
+    /// cancel the canned cycle
+    G80,
+    /// drilling cycle
+    G81(CannedCycle),
+    /// drilling cycle with a dwell at the bottom
+    G82(CannedCycle),
+    /// peck drilling cycle
+    G83(CannedCycle),
+    /// right-hand tapping cycle
+    G84(CannedCycle),
+    /// boring cycle, feed out
+    G85(CannedCycle),
+    /// boring cycle, spindle stop, rapid out
+    G86(CannedCycle),
+    /// back boring cycle
+    G87(CannedCycle),
+    /// boring cycle, spindle stop, manual out
+    G88(CannedCycle),
+    /// boring cycle, dwell, feed out
+    G89(CannedCycle),
+    /// machine coordinates for the `G0` / `G1` of the same block, not modal
     G53,
     /// coordinate system 1
     G54,
     /// coordinate system 2
     G55,
-    /* todo - G56 -> G59.3 */
+    /// coordinate system 3
+    G56,
+    /// coordinate system 4
+    G57,
+    /// coordinate system 5
+    G58,
+    /// coordinate system 6
+    G59,
+    /// coordinate system 7
+    G59_1,
+    /// coordinate system 8
+    G59_2,
+    /// coordinate system 9
+    G59_3,
     /// absolute positioning
     G90,
     /// relative positioning
     G91,
+    /// set axis offsets so that the current position has the given coordinates
+    G92(Axes),
+    /// reset axis offsets and their parameters
+    G92_1,
+    /// reset axis offsets, keeping their parameters
+    G92_2,
+    /// restore axis offsets from their parameters
+    G92_3,
+    /// inverse time feed mode, `F` is the number of moves per minute
+    G93,
+    /// units per minute feed mode
+    G94,
+    /// units per revolution feed mode
+    G95,
+    /// constant surface speed, `S` is the surface speed, with the `D` maximum spindle
+    /// speed
+    G96(Option<Expression>),
+    /// `S` is the spindle speed in revolutions per minute
+    G97,
+    /// canned cycles retract to the start level, or `R` if it is higher
+    G98,
+    /// canned cycles retract to `R`
+    G99,
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Mcode {
+    /// pause the program
+    M0,
+    /// pause the program if the optional stop switch is on
+    M1,
+    /// end the program
+    M2,
+    /// spindle clockwise
     M3,
+    /// spindle counter-clockwise
     M4,
+    /// spindle stop
     M5,
     M6(Option<Tcode>),
+    /// mist coolant on
     M7,
+    /// flood coolant on
     M8,
+    /// all coolant off
     M9,
+    /// exchange pallets and end the program
+    M30,
+    /// exchange pallets and pause the program
+    M60,
 }
 
+/// `O` word, the number ties together the statements of one control structure
 #[derive(Debug, PartialEq, Clone)]
 pub struct Ocode {
-    id: u32,
-    statement: OcodeStatement,
+    pub id: u32,
+    pub statement: OcodeStatement,
 }
 
 impl Ocode {
@@ -77,9 +276,27 @@ impl Ocode {
 #[derive(Debug, PartialEq, Clone)]
 pub enum OcodeStatement {
     Sub,
-    EndSub,
+    /// ends the subroutine, the value is returned in `#<_value>`
+    EndSub(Option<Expression>),
+    /// leaves the subroutine before its `endsub`, with the value it returns
+    Return(Option<Expression>),
+    /// runs the subroutine with the same number, with the arguments in `#1` to `#30`
+    Call(Vec<Expression>),
     If(Expression),
+    ElseIf(Expression),
+    Else,
     EndIf,
+    /// starts a `while` loop, or ends a `do` loop with the same number
+    While(Expression),
+    EndWhile,
+    Do,
+    /// runs its body the given number of times
+    Repeat(Expression),
+    EndRepeat,
+    /// leaves the loop with the same number
+    Break,
+    /// skips to the next iteration of the loop with the same number
+    Continue,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -89,9 +306,9 @@ pub struct Scode(pub Expression);
 pub struct Tcode(pub Expression);
 
 #[derive(Debug, PartialEq, Clone)]
-pub struct Fcode(pub f32);
+pub struct Fcode(pub Expression);
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Axis {
     X,
     Y,
@@ -113,7 +330,17 @@ impl Axis {
             _ => None,
         }
     }
-    fn to_idx(&self) -> usize {
+    pub fn to_chr(self) -> char {
+        match self {
+            Axis::X => 'X',
+            Axis::Y => 'Y',
+            Axis::Z => 'Z',
+            Axis::A => 'A',
+            Axis::B => 'B',
+            Axis::C => 'C',
+        }
+    }
+    pub fn to_idx(self) -> usize {
         match self {
             Axis::X => 0,
             Axis::Y => 1,
@@ -132,14 +359,196 @@ impl Axes {
         Self([const { None }; NUM_AXES])
     }
     pub fn get(&self, axis: Axis) -> Option<&Expression> {
-        self.0[axis.to_idx()].as_ref()
+        self.0.get(axis.to_idx())?.as_ref()
     }
+    /// Axes beyond the `NUM_AXES` of the machine are ignored
     pub fn set(mut self, axis: Axis, value: Expression) -> Self {
-        self.0[axis.to_idx()] = Some(value);
+        if let Some(word) = self.word_mut(axis) {
+            *word = Some(value);
+        }
         self
     }
+    /// The word of `axis`, for parsers to fill in, `None` beyond the `NUM_AXES` of the
+    /// machine
+    pub fn word_mut(&mut self, axis: Axis) -> Option<&mut Option<Expression>> {
+        self.0.get_mut(axis.to_idx())
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(Option::is_none)
+    }
+    /// (axis index, value) of every axis with a word
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &Expression)> + '_ {
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, value)| value.as_ref().map(|value| (idx, value)))
+    }
 }
 
+/// `new` and a setter for each word of a struct of words, `axis` style setters for
+/// its `Axes` fields
+macro_rules! word_builder {
+    ($ty:ident { $($axes:ident: $axis:ident),* }; $($word:ident),*) => {
+        impl $ty {
+            pub fn new() -> Self {
+                Self::default()
+            }
+            $(pub fn $axis(self, axis: Axis, value: Expression) -> Self {
+                Self {
+                    $axes: self.$axes.set(axis, value),
+                    ..self
+                }
+            })*
+            $(pub fn $word(self, value: Expression) -> Self {
+                Self {
+                    $word: Some(value),
+                    ..self
+                }
+            })*
+        }
+    };
+}
+
+/// Words of a `G2` / `G3` arc
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct Arc {
+    /// end point, and the helical axis
+    pub axes: Axes,
+    /// `I`, `J`, `K` center offsets from the start point, stored by the axis they offset
+    pub offsets: Axes,
+    /// `R` radius, negative for arcs over 180 degrees
+    pub radius: Option<Expression>,
+    /// `P` number of turns
+    pub turns: Option<Expression>,
+}
+
+word_builder!(Arc { axes: axis, offsets: offset }; radius, turns);
+
+/// Words of a `G10`
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct SetOffsets {
+    /// `L` kind of data to set
+    pub l: Option<Expression>,
+    /// `P` coordinate system, 0 for the active one, or tool number
+    pub p: Option<Expression>,
+    pub axes: Axes,
+    /// `R` tool radius
+    pub r: Option<Expression>,
+    /// `I` tool front angle
+    pub i: Option<Expression>,
+    /// `J` tool back angle
+    pub j: Option<Expression>,
+    /// `Q` tool orientation
+    pub q: Option<Expression>,
+}
+
+word_builder!(SetOffsets { axes: axis }; l, p, r, i, j, q);
+
+/// Words of a `G43.2`
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct ToolOffset {
+    /// `H` tool whose offset is added
+    pub h: Option<Expression>,
+    /// offsets added to the given axes
+    pub axes: Axes,
+}
+
+word_builder!(ToolOffset { axes: axis }; h);
+
+/// Words of a `G33` or `G33.1`
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct SyncedMotion {
+    pub axes: Axes,
+    /// `K` distance travelled per spindle revolution
+    pub k: Option<Expression>,
+}
+
+word_builder!(SyncedMotion { axes: axis }; k);
+
+/// Words of a `G76`
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct Threading {
+    /// `Z` end of the thread
+    pub z: Option<Expression>,
+    /// `P` thread pitch
+    pub p: Option<Expression>,
+    /// `I` offset of the thread peak from the drive line, negative for external threads
+    pub i: Option<Expression>,
+    /// `J` depth of the first cut beyond the thread peak
+    pub j: Option<Expression>,
+    /// `R` depth degression
+    pub r: Option<Expression>,
+    /// `K` full thread depth beyond the thread peak
+    pub k: Option<Expression>,
+    /// `Q` compound slide angle in degrees
+    pub q: Option<Expression>,
+    /// `H` number of spring passes at full depth
+    pub h: Option<Expression>,
+    /// `E` length of the tapers along Z
+    pub e: Option<Expression>,
+    /// `L` tapers: 0 none, 1 entry, 2 exit, 3 both
+    pub l: Option<Expression>,
+}
+
+word_builder!(Threading {}; z, p, i, j, r, k, q, h, e, l);
+
+/// Words of a `G5` or `G5.1`
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct Spline {
+    /// end point
+    pub axes: Axes,
+    /// `I`, `J` offset of the first control point from the start
+    pub i: Option<Expression>,
+    pub j: Option<Expression>,
+    /// `P`, `Q` offset of the second control point of a `G5` from the end
+    pub p: Option<Expression>,
+    pub q: Option<Expression>,
+}
+
+word_builder!(Spline { axes: axis }; i, j, p, q);
+
+/// Words of a NURBS control point, on a `G5.2` or a line of its own
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct NurbsPoint {
+    pub axes: Axes,
+    /// `P` weight
+    pub p: Option<Expression>,
+    /// `L` order, on the `G5.2` starting the curve
+    pub l: Option<Expression>,
+}
+
+word_builder!(NurbsPoint { axes: axis }; p, l);
+
+/// Words of a `G64`
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct Blending {
+    /// `P` path tolerance
+    pub p: Option<Expression>,
+    /// `Q` naive CAM tolerance, moves within it of a straight line are merged
+    pub q: Option<Expression>,
+}
+
+word_builder!(Blending {}; p, q);
+
+/// Words of a canned cycle, `G73`, `G74` and `G81` to `G89`
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct CannedCycle {
+    /// position of the hole in the plane, and its bottom along the normal axis
+    pub axes: Axes,
+    /// `R` retract level
+    pub r: Option<Expression>,
+    /// `Q` peck depth
+    pub q: Option<Expression>,
+    /// `P` dwell at the bottom
+    pub p: Option<Expression>,
+    /// `L` number of repeats
+    pub l: Option<Expression>,
+    /// `I`, `J`, `K` of `G87`, stored by the axis they offset
+    pub offsets: Axes,
+}
+
+word_builder!(CannedCycle { axes: axis, offsets: offset }; r, q, p, l);
+
 macro_rules! from_impl {
     ($($name:ident $ty:ident),+) => {
         $(
@@ -155,7 +564,7 @@ macro_rules! from_impl {
     };
 }
 
-from_impl!(M Mcode, S Scode, T Tcode);
+from_impl!(M Mcode, F Fcode, S Scode, T Tcode);
 
 impl From<Ocode> for Command {
     fn from(t: Ocode) -> Self {
@@ -0,0 +1,158 @@
+//! Arc center calculation and validation, following the checks done by LinuxCNC's
+//! `arc_data_ijk` and `arc_data_r`. All lengths are in mm.
+
+use super::{
+    math::{atan2, sqrt},
+    model_state::ModelStateUnit,
+};
+
+/// an end point may be this far off the arc (`R`) in inches / mm
+const TOLERANCE_INCH: f64 = 0.0005;
+const TOLERANCE_MM: f64 = 0.005;
+/// arcs with a radius below this are rejected, in inches / mm
+const RADIUS_TOLERANCE_INCH: f64 = 0.00005;
+const RADIUS_TOLERANCE_MM: f64 = RADIUS_TOLERANCE_INCH * 25.4;
+/// start and end radius of an `IJK` arc may differ by this much, in inches / mm
+const CENTER_ARC_RADIUS_TOLERANCE_INCH: f64 = 2.0 * 0.001 * core::f64::consts::SQRT_2;
+const CENTER_ARC_RADIUS_TOLERANCE_MM: f64 = 2.0 * 0.01 * core::f64::consts::SQRT_2;
+/// ... or differ relatively by this much, when also above the absolute tolerance
+const SPIRAL_RELATIVE_TOLERANCE: f64 = 0.001;
+const TINY: f64 = 1e-12;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ArcError {
+    /// both `R` and `I` / `J` / `K` given
+    MixedRadiusOffsets,
+    /// neither `R` nor `I` / `J` / `K` given
+    MissingRadiusOffsets,
+    /// offset given for the axis normal to the plane, e.g. `K` in the XY plane
+    OffsetOutOfPlane,
+    /// arcs in the UV, UW and VW planes are not supported
+    UnsupportedPlane,
+    ZeroRadius,
+    /// distance from the center to the end point differs from the distance to the start point
+    RadiusMismatch {
+        start: f64,
+        end: f64,
+    },
+    /// `R` arcs cannot end where they start
+    EndSameAsStart,
+    /// `R` is less than half the distance to the end point
+    RadiusTooSmall,
+    /// `P` is not a positive integer
+    InvalidTurns(f32),
+}
+
+/// Point in the arc plane
+pub type PlanePoint = [f64; 2];
+
+fn tolerance(unit: &ModelStateUnit, inch: f64, mm: f64) -> f64 {
+    match unit {
+        ModelStateUnit::In => unit.to_mm(inch),
+        ModelStateUnit::Mm => mm,
+    }
+}
+
+fn distance(a: PlanePoint, b: PlanePoint) -> f64 {
+    let dx = b[0] - a[0];
+    let dy = b[1] - a[1];
+    sqrt(dx * dx + dy * dy)
+}
+
+/// Center of an arc given as an offset from its start point, checking that the end
+/// point lies on the same circle
+pub fn center_from_offsets(
+    start: PlanePoint,
+    end: PlanePoint,
+    offset: PlanePoint,
+    unit: &ModelStateUnit,
+) -> Result<PlanePoint, ArcError> {
+    let center = [start[0] + offset[0], start[1] + offset[1]];
+    let start_radius = distance(center, start);
+    let end_radius = distance(center, end);
+
+    let radius_tolerance = tolerance(unit, RADIUS_TOLERANCE_INCH, RADIUS_TOLERANCE_MM);
+    if start_radius < radius_tolerance || end_radius < radius_tolerance {
+        return Err(ArcError::ZeroRadius);
+    }
+
+    let abs_tolerance = tolerance(
+        unit,
+        CENTER_ARC_RADIUS_TOLERANCE_INCH,
+        CENTER_ARC_RADIUS_TOLERANCE_MM,
+    );
+    let abs_err = (start_radius - end_radius).abs();
+    let rel_err = abs_err / start_radius.max(end_radius);
+    if abs_err > abs_tolerance * 100.0
+        || (rel_err > SPIRAL_RELATIVE_TOLERANCE && abs_err > abs_tolerance)
+    {
+        return Err(ArcError::RadiusMismatch {
+            start: start_radius,
+            end: end_radius,
+        });
+    }
+    Ok(center)
+}
+
+/// Center of an arc given by its radius, a negative radius selects the arc over 180 degrees
+pub fn center_from_radius(
+    start: PlanePoint,
+    end: PlanePoint,
+    radius: f64,
+    clockwise: bool,
+    unit: &ModelStateUnit,
+) -> Result<PlanePoint, ArcError> {
+    if start == end {
+        return Err(ArcError::EndSameAsStart);
+    }
+    let abs_radius = radius.abs();
+    let mid = [(start[0] + end[0]) / 2.0, (start[1] + end[1]) / 2.0];
+    let mut half_length = distance(mid, end);
+    if half_length - abs_radius > tolerance(unit, TOLERANCE_INCH, TOLERANCE_MM) {
+        return Err(ArcError::RadiusTooSmall);
+    }
+    // allow a small error for semicircles
+    if half_length / abs_radius > 1.0 - TINY {
+        half_length = abs_radius;
+    }
+
+    // the center is on the perpendicular bisector of the chord, to the left of it
+    // for counter-clockwise arcs up to 180 degrees
+    let offset = sqrt(abs_radius * abs_radius - half_length * half_length);
+    let offset = if clockwise == (radius > 0.0) {
+        -offset
+    } else {
+        offset
+    };
+    let chord_length = distance(start, end);
+    let left = [
+        -(end[1] - start[1]) / chord_length,
+        (end[0] - start[0]) / chord_length,
+    ];
+    Ok([mid[0] + offset * left[0], mid[1] + offset * left[1]])
+}
+
+/// Length of a helical arc around `center`, `turns` as in
+/// [`ArcMove`](super::motion::ArcMove), with `normal` the distance travelled along the
+/// axis normal to the plane
+pub fn length(
+    start: PlanePoint,
+    end: PlanePoint,
+    center: PlanePoint,
+    turns: i32,
+    normal: f64,
+) -> f64 {
+    use core::f64::consts::TAU;
+    let angle = |point: PlanePoint| atan2(point[1] - center[1], point[0] - center[0]);
+    let mut sweep = angle(end) - angle(start);
+    if turns < 0 {
+        sweep = -sweep;
+    }
+    // a full circle when the end point is the start point
+    if sweep <= 0.0 {
+        sweep += TAU;
+    }
+    sweep += (turns.abs() - 1) as f64 * TAU;
+    let planar = distance(start, center) * sweep;
+    sqrt(planar * planar + normal * normal)
+}
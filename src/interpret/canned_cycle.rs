@@ -0,0 +1,221 @@
+//! Canned cycles, expanded into rapids, feeds and dwells following LinuxCNC's
+//! `convert_cycle_*`. All lengths are in mm, in machine coordinates.
+
+use super::{
+    model_state::{MmSec, PathMode, Position},
+    motion::{MotionEvent, Move, MoveKind},
+};
+use crate::NUM_AXES;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+/// Clearance above the previous peck when `G73` / `G83` go back down, 0.010 inch
+const RAPID_DELTA: f64 = 0.254;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CycleKind {
+    /// drilling with chip breaking
+    G73,
+    /// left-hand tapping
+    G74,
+    /// drilling
+    G81,
+    /// drilling with a dwell at the bottom
+    G82,
+    /// peck drilling
+    G83,
+    /// right-hand tapping
+    G84,
+    /// boring, feed out
+    G85,
+    /// boring, spindle stop, rapid out
+    G86,
+    /// back boring
+    G87,
+    /// boring, spindle stop, manual out
+    G88,
+    /// boring, dwell, feed out
+    G89,
+}
+
+impl CycleKind {
+    /// whether the cycle needs the `Q` peck depth
+    pub fn pecks(self) -> bool {
+        matches!(self, CycleKind::G73 | CycleKind::G83)
+    }
+
+    /// whether the cycle needs the `P` dwell
+    pub fn dwells(self) -> bool {
+        matches!(
+            self,
+            CycleKind::G82 | CycleKind::G86 | CycleKind::G88 | CycleKind::G89
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum CycleError {
+    /// canned cycles in the UVW planes are not supported
+    UnsupportedPlane,
+    /// the bottom of the hole is above `R`
+    BottomAboveR,
+    /// `Q` is not positive
+    InvalidPeck(f32),
+    /// `L` is not a positive integer
+    InvalidRepeats(f32),
+}
+
+/// A canned cycle with its levels along the drilling axis
+#[derive(Debug, PartialEq, Clone)]
+pub struct Cycle {
+    pub kind: CycleKind,
+    /// index of the axis drilled along, normal to the plane
+    pub normal: usize,
+    /// `R` level
+    pub retract: f64,
+    pub bottom: f64,
+    /// level after each hole, `R` for `G99`, or the higher of `R` and the start for `G98`
+    pub clear: f64,
+    /// `Q` of `G73` / `G83`
+    pub peck: f64,
+    /// `P` at the bottom of the hole
+    pub dwell: Duration,
+    /// `I`, `J` of `G87`, moving the tool off center to pass through the hole
+    pub offset: Position<NUM_AXES>,
+    /// `K` of `G87`, the level the back bore is fed up to
+    pub middle: f64,
+    pub feedrate: MmSec,
+    pub path_mode: PathMode,
+}
+
+impl Cycle {
+    /// Events for drilling `holes` from `start`. Only the plane coordinates of the
+    /// holes are used.
+    pub fn expand(
+        &self,
+        start: Position<NUM_AXES>,
+        holes: &[Position<NUM_AXES>],
+    ) -> Vec<MotionEvent> {
+        let mut path = Path {
+            position: start,
+            normal: self.normal,
+            feedrate: self.feedrate,
+            path_mode: self.path_mode,
+            events: Vec::new(),
+        };
+        if start[self.normal] < self.retract {
+            path.rapid(self.retract);
+        }
+        for hole in holes {
+            let mut above = *hole;
+            above[self.normal] = path.position[self.normal];
+            path.rapid_to(above);
+            path.rapid(self.retract);
+            self.hole(&mut path);
+        }
+        path.events
+    }
+
+    fn hole(&self, path: &mut Path) {
+        match self.kind {
+            CycleKind::G81 => {
+                path.feed(self.bottom);
+                path.rapid(self.clear);
+            }
+            CycleKind::G82 | CycleKind::G86 | CycleKind::G88 => {
+                path.feed(self.bottom);
+                path.dwell(self.dwell);
+                path.rapid(self.clear);
+            }
+            CycleKind::G73 | CycleKind::G83 => {
+                let mut depth = self.retract - self.peck;
+                while depth > self.bottom {
+                    path.feed(depth);
+                    // G83 clears the chips out of the hole, G73 only breaks them
+                    if self.kind == CycleKind::G83 {
+                        path.rapid(self.retract);
+                    }
+                    path.rapid(depth + RAPID_DELTA);
+                    depth -= self.peck;
+                }
+                path.feed(self.bottom);
+                path.rapid(self.clear);
+            }
+            CycleKind::G74 | CycleKind::G84 | CycleKind::G85 => {
+                path.feed(self.bottom);
+                path.feed(self.retract);
+                path.rapid(self.clear);
+            }
+            CycleKind::G89 => {
+                path.feed(self.bottom);
+                path.dwell(self.dwell);
+                path.feed(self.retract);
+                path.rapid(self.clear);
+            }
+            CycleKind::G87 => {
+                let center = path.position;
+                let mut off_center = center;
+                for idx in 0..NUM_AXES {
+                    off_center[idx] += self.offset[idx];
+                }
+                path.rapid_to(off_center);
+                path.rapid(self.bottom);
+                let mut bottom = center;
+                bottom[self.normal] = self.bottom;
+                path.rapid_to(bottom);
+                path.feed(self.middle);
+                path.feed(self.bottom);
+                off_center[self.normal] = self.bottom;
+                path.rapid_to(off_center);
+                path.rapid(self.clear);
+                let mut clear = center;
+                clear[self.normal] = self.clear;
+                path.rapid_to(clear);
+            }
+        }
+    }
+}
+
+/// Events of a cycle, skipping moves which go nowhere
+struct Path {
+    position: Position<NUM_AXES>,
+    normal: usize,
+    feedrate: MmSec,
+    path_mode: PathMode,
+    events: Vec<MotionEvent>,
+}
+
+impl Path {
+    fn rapid_to(&mut self, to: Position<NUM_AXES>) {
+        self.move_to(MoveKind::Rapid, to, MmSec::default());
+    }
+
+    fn rapid(&mut self, level: f64) {
+        let mut to = self.position;
+        to[self.normal] = level;
+        self.rapid_to(to);
+    }
+
+    fn feed(&mut self, level: f64) {
+        let mut to = self.position;
+        to[self.normal] = level;
+        self.move_to(MoveKind::Linear, to, self.feedrate);
+    }
+
+    fn dwell(&mut self, duration: Duration) {
+        self.events.push(MotionEvent::Dwell(duration));
+    }
+
+    fn move_to(&mut self, kind: MoveKind, to: Position<NUM_AXES>, feedrate: MmSec) {
+        if to == self.position {
+            return;
+        }
+        self.events.push(MotionEvent::Move(Move {
+            kind,
+            to,
+            feedrate,
+            path_mode: self.path_mode,
+        }));
+        self.position = to;
+    }
+}
@@ -0,0 +1,461 @@
+//! Cutter radius compensation, keeping the tool a radius to the left (`G41`) or
+//! right (`G42`) of the programmed path. Each move is held back until the next one,
+//! which decides the corner between them: convex corners get an arc around the
+//! programmed corner, concave corners are trimmed to where the offset paths meet,
+//! as in LinuxCNC. All lengths are in mm.
+
+use super::{
+    arc::PlanePoint,
+    math::sqrt,
+    model_state::Position,
+    motion::{ArcMove, MotionEvent, Move, MoveKind},
+};
+use crate::NUM_AXES;
+use alloc::{vec, vec::Vec};
+
+/// moves whose directions differ by less than this (sine of the angle) are tangent
+const TANGENT_TOLERANCE: f64 = 1e-6;
+const TINY: f64 = 1e-9;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum CompError {
+    /// `G41` / `G42` while compensation is already on
+    AlreadyOn,
+    /// compensation is only supported in the XY and XZ planes
+    UnsupportedPlane,
+    /// `D` is not a tool number, or is a negative diameter
+    InvalidD(f32),
+    /// the first move with compensation on is an arc
+    EntryArc,
+    /// the first move with compensation on is not longer than the tool radius
+    EntryTooShort,
+    /// the tool is on the inside of an arc with a smaller radius
+    ArcTooSmall,
+    /// concave corner the tool cannot reach without cutting into the part
+    Gouge,
+    /// command not allowed with compensation on, e.g. `G53`, `G28` or a plane change
+    NotAllowed,
+}
+
+/// A programmed move, in machine coordinates
+#[derive(Debug, Clone)]
+pub struct Segment {
+    pub from: Position<NUM_AXES>,
+    pub mv: Move,
+}
+
+/// Compensated path being built
+#[derive(Debug, Default)]
+pub struct CompPath {
+    /// axis indices of the compensation plane
+    plane: (usize, usize),
+    /// distance of the tool from the path, positive to the left
+    offset: f64,
+    pending: Option<Pending>,
+    /// events after the pending move which do not move in the plane, e.g. a plunge
+    queued: Vec<MotionEvent>,
+}
+
+#[derive(Debug)]
+struct Pending {
+    segment: Segment,
+    /// where the tool starts the move, the programmed start for the entry move
+    start: PlanePoint,
+}
+
+/// Offset path of a move, used to find concave corners
+enum OffsetPath {
+    Line { start: PlanePoint, end: PlanePoint },
+    Circle { center: PlanePoint, radius: f64 },
+}
+
+impl CompPath {
+    pub fn new(plane: (usize, usize), offset: f64) -> Self {
+        Self {
+            plane,
+            offset,
+            ..Self::default()
+        }
+    }
+
+    /// Add a programmed move, returning the events which are now final
+    pub fn push(&mut self, segment: Segment) -> Result<Vec<MotionEvent>, CompError> {
+        let start = self.plane_point(&segment.from);
+        let end = self.plane_point(&segment.mv.to);
+        let center = self.arc_center(&segment);
+        if center.is_none() && distance(start, end) < TINY {
+            return Ok(self.push_event(MotionEvent::Move(segment.mv)));
+        }
+        if center.is_some() && self.offset_radius(&segment) <= TINY {
+            return Err(CompError::ArcTooSmall);
+        }
+
+        let Some(pending) = self.pending.take() else {
+            // entry move, from the uncompensated position
+            if center.is_some() {
+                return Err(CompError::EntryArc);
+            }
+            if distance(start, end) <= self.offset.abs() {
+                return Err(CompError::EntryTooShort);
+            }
+            self.pending = Some(Pending { segment, start });
+            return Ok(Vec::new());
+        };
+
+        let (events, next_start) = self.corner(pending, &segment)?;
+        self.pending = Some(Pending {
+            segment,
+            start: next_start,
+        });
+        Ok(events)
+    }
+
+    /// Add an event without motion in the plane, held back behind the pending move
+    pub fn push_event(&mut self, event: MotionEvent) -> Vec<MotionEvent> {
+        if self.pending.is_some() {
+            self.queued.push(event);
+            Vec::new()
+        } else {
+            vec![event]
+        }
+    }
+
+    /// End compensation, the pending move ends a radius from its programmed end
+    pub fn finish(&mut self) -> Vec<MotionEvent> {
+        match self.pending.take() {
+            Some(pending) => {
+                let corner = self.plane_point(&pending.segment.mv.to);
+                let end = self.offset_point(&pending.segment, corner);
+                self.emit(pending.segment, end, None)
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Finish the pending move at the corner with `next`, returning its events and
+    /// where the tool starts `next`
+    fn corner(
+        &mut self,
+        pending: Pending,
+        next: &Segment,
+    ) -> Result<(Vec<MotionEvent>, PlanePoint), CompError> {
+        let current = &pending.segment;
+        let corner = self.plane_point(&current.mv.to);
+        let current_end = self.offset_point(current, corner);
+        let next_start = self.offset_point(next, corner);
+
+        let current_tangent = self.tangent(current, corner);
+        let next_tangent = self.tangent(next, corner);
+        let turn = cross(current_tangent, next_tangent);
+        let tangent = turn.abs() < TANGENT_TOLERANCE;
+        if self.offset == 0.0 || (tangent && dot(current_tangent, next_tangent) > 0.0) {
+            return Ok((self.emit(pending.segment, current_end, None), next_start));
+        }
+
+        if tangent || turn * self.offset < 0.0 {
+            // convex, go around the programmed corner
+            let mut center = next.from;
+            self.set_plane_point(&mut center, corner);
+            let mut to = next.from;
+            self.set_plane_point(&mut to, next_start);
+            let feedrate = if next.mv.feedrate.0 > 0.0 {
+                next.mv.feedrate
+            } else {
+                current.mv.feedrate
+            };
+            let corner_arc = Move {
+                kind: MoveKind::Arc(ArcMove {
+                    center,
+                    turns: if self.offset > 0.0 { -1 } else { 1 },
+                }),
+                to,
+                feedrate,
+                path_mode: next.mv.path_mode,
+            };
+            let events = self.emit(pending.segment, current_end, Some(corner_arc));
+            return Ok((events, next_start));
+        }
+
+        // concave, both moves end where their offset paths meet
+        let current_path = self.offset_path(current, pending.start, current_end);
+        let next_end = self.offset_point(next, self.plane_point(&next.mv.to));
+        let next_path = self.offset_path(next, next_start, next_end);
+        let meet = intersect(&current_path, &next_path, corner).ok_or(CompError::Gouge)?;
+        if !self.before_end(current, &current_path, pending.start, current_end, meet)
+            || !self.before_end(next, &next_path, next_start, next_end, meet)
+        {
+            return Err(CompError::Gouge);
+        }
+        Ok((self.emit(pending.segment, meet, None), meet))
+    }
+
+    /// Whether `point` on the offset path of `segment` is between `start` and `end`,
+    /// so that trimming the move there does not reverse it
+    fn before_end(
+        &self,
+        segment: &Segment,
+        path: &OffsetPath,
+        start: PlanePoint,
+        end: PlanePoint,
+        point: PlanePoint,
+    ) -> bool {
+        match path {
+            OffsetPath::Line { .. } => {
+                let direction = sub(end, start);
+                let t = dot(sub(point, start), direction) / dot(direction, direction);
+                t > 0.0 && t < 1.0 + TANGENT_TOLERANCE
+            }
+            OffsetPath::Circle { center, .. } => {
+                let counter_clockwise = self.counter_clockwise(segment);
+                // full circles end where they start
+                distance(start, end) < TINY
+                    || sweep(*center, start, point, counter_clockwise)
+                        <= sweep(*center, start, end, counter_clockwise) + TANGENT_TOLERANCE
+            }
+        }
+    }
+
+    /// The move of `segment` ending at `end`, followed by the queued events and `corner`
+    fn emit(
+        &mut self,
+        segment: Segment,
+        end: PlanePoint,
+        corner: Option<Move>,
+    ) -> Vec<MotionEvent> {
+        let mut mv = segment.mv;
+        self.set_plane_point(&mut mv.to, end);
+        let mut events = vec![MotionEvent::Move(mv)];
+        for mut event in self.queued.drain(..) {
+            if let MotionEvent::Move(mv) = &mut event {
+                mv.to[self.plane.0] = end[0];
+                mv.to[self.plane.1] = end[1];
+            }
+            events.push(event);
+        }
+        events.extend(corner.map(MotionEvent::Move));
+        events
+    }
+
+    fn plane_point(&self, position: &Position<NUM_AXES>) -> PlanePoint {
+        [position[self.plane.0], position[self.plane.1]]
+    }
+
+    fn set_plane_point(&self, position: &mut Position<NUM_AXES>, point: PlanePoint) {
+        position[self.plane.0] = point[0];
+        position[self.plane.1] = point[1];
+    }
+
+    fn arc_center(&self, segment: &Segment) -> Option<PlanePoint> {
+        match &segment.mv.kind {
+            MoveKind::Arc(arc) => Some(self.plane_point(&arc.center)),
+            _ => None,
+        }
+    }
+
+    fn counter_clockwise(&self, segment: &Segment) -> bool {
+        match &segment.mv.kind {
+            MoveKind::Arc(arc) => arc.turns > 0,
+            _ => false,
+        }
+    }
+
+    /// Unit direction of travel at `point` on the programmed move
+    fn tangent(&self, segment: &Segment, point: PlanePoint) -> PlanePoint {
+        match self.arc_center(segment) {
+            Some(center) => {
+                let radial = unit(sub(point, center));
+                if self.counter_clockwise(segment) {
+                    [-radial[1], radial[0]]
+                } else {
+                    [radial[1], -radial[0]]
+                }
+            }
+            None => {
+                let start = self.plane_point(&segment.from);
+                let end = self.plane_point(&segment.mv.to);
+                unit(sub(end, start))
+            }
+        }
+    }
+
+    /// Tool position for `point` on the programmed move
+    fn offset_point(&self, segment: &Segment, point: PlanePoint) -> PlanePoint {
+        let tangent = self.tangent(segment, point);
+        [
+            point[0] - self.offset * tangent[1],
+            point[1] + self.offset * tangent[0],
+        ]
+    }
+
+    /// Radius of the tool path of an arc
+    fn offset_radius(&self, segment: &Segment) -> f64 {
+        let center = self.arc_center(segment).unwrap_or_default();
+        let radius = distance(center, self.plane_point(&segment.from));
+        // the left of a counter-clockwise arc is its inside
+        if self.counter_clockwise(segment) {
+            radius - self.offset
+        } else {
+            radius + self.offset
+        }
+    }
+
+    fn offset_path(&self, segment: &Segment, start: PlanePoint, end: PlanePoint) -> OffsetPath {
+        match self.arc_center(segment) {
+            Some(center) => OffsetPath::Circle {
+                center,
+                radius: self.offset_radius(segment),
+            },
+            None => OffsetPath::Line { start, end },
+        }
+    }
+}
+
+/// Point where two offset paths meet, the one closest to `near` if there are two
+fn intersect(a: &OffsetPath, b: &OffsetPath, near: PlanePoint) -> Option<PlanePoint> {
+    let closest = |points: [PlanePoint; 2]| {
+        if distance(points[0], near) <= distance(points[1], near) {
+            points[0]
+        } else {
+            points[1]
+        }
+    };
+    match (a, b) {
+        (
+            OffsetPath::Line { start, end },
+            OffsetPath::Line {
+                start: other_start,
+                end: other_end,
+            },
+        ) => {
+            let direction = sub(*end, *start);
+            let other_direction = sub(*other_end, *other_start);
+            let denominator = cross(direction, other_direction);
+            if denominator.abs() < TINY {
+                return None;
+            }
+            let t = cross(sub(*other_start, *start), other_direction) / denominator;
+            Some(add(*start, scale(direction, t)))
+        }
+        (OffsetPath::Line { start, end }, OffsetPath::Circle { center, radius })
+        | (OffsetPath::Circle { center, radius }, OffsetPath::Line { start, end }) => {
+            line_circle(*start, *end, *center, *radius).map(closest)
+        }
+        (
+            OffsetPath::Circle { center, radius },
+            OffsetPath::Circle {
+                center: other_center,
+                radius: other_radius,
+            },
+        ) => circle_circle(*center, *radius, *other_center, *other_radius).map(closest),
+    }
+}
+
+fn line_circle(
+    start: PlanePoint,
+    end: PlanePoint,
+    center: PlanePoint,
+    radius: f64,
+) -> Option<[PlanePoint; 2]> {
+    let direction = unit(sub(end, start));
+    // closest point of the line to the center, then along the line both ways
+    let along = dot(sub(center, start), direction);
+    let foot = add(start, scale(direction, along));
+    let foot_distance = distance(foot, center);
+    if foot_distance > radius {
+        return None;
+    }
+    let half_chord = sqrt(radius * radius - foot_distance * foot_distance);
+    Some([
+        add(foot, scale(direction, -half_chord)),
+        add(foot, scale(direction, half_chord)),
+    ])
+}
+
+fn circle_circle(
+    center: PlanePoint,
+    radius: f64,
+    other_center: PlanePoint,
+    other_radius: f64,
+) -> Option<[PlanePoint; 2]> {
+    let between = distance(center, other_center);
+    if between < TINY || between > radius + other_radius || between < (radius - other_radius).abs()
+    {
+        return None;
+    }
+    let direction = unit(sub(other_center, center));
+    // distance from `center` to the chord through both intersections
+    let along =
+        (radius * radius - other_radius * other_radius + between * between) / (2.0 * between);
+    let half_chord = sqrt((radius * radius - along * along).max(0.0));
+    let foot = add(center, scale(direction, along));
+    let normal = [-direction[1], direction[0]];
+    Some([
+        add(foot, scale(normal, -half_chord)),
+        add(foot, scale(normal, half_chord)),
+    ])
+}
+
+/// Angle swept going around `center` from `from` to `to`, as a pseudo-angle in
+/// `[0, 4)` which orders the same as the angle
+fn sweep(center: PlanePoint, from: PlanePoint, to: PlanePoint, counter_clockwise: bool) -> f64 {
+    let from = pseudo_angle(sub(from, center));
+    let to = pseudo_angle(sub(to, center));
+    let sweep = if counter_clockwise {
+        to - from
+    } else {
+        from - to
+    };
+    if sweep < 0.0 {
+        sweep + 4.0
+    } else {
+        sweep
+    }
+}
+
+/// Angle of a vector as a number in `[0, 4)` growing counter-clockwise, without trig
+fn pseudo_angle(vector: PlanePoint) -> f64 {
+    let [x, y] = vector;
+    let ratio = y / (x.abs() + y.abs());
+    if x >= 0.0 {
+        if ratio < 0.0 {
+            4.0 + ratio
+        } else {
+            ratio
+        }
+    } else {
+        2.0 - ratio
+    }
+}
+
+fn add(a: PlanePoint, b: PlanePoint) -> PlanePoint {
+    [a[0] + b[0], a[1] + b[1]]
+}
+
+fn sub(a: PlanePoint, b: PlanePoint) -> PlanePoint {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+fn scale(a: PlanePoint, factor: f64) -> PlanePoint {
+    [a[0] * factor, a[1] * factor]
+}
+
+fn dot(a: PlanePoint, b: PlanePoint) -> f64 {
+    a[0] * b[0] + a[1] * b[1]
+}
+
+fn cross(a: PlanePoint, b: PlanePoint) -> f64 {
+    a[0] * b[1] - a[1] * b[0]
+}
+
+fn distance(a: PlanePoint, b: PlanePoint) -> f64 {
+    let difference = sub(b, a);
+    sqrt(dot(difference, difference))
+}
+
+fn unit(a: PlanePoint) -> PlanePoint {
+    let length = sqrt(dot(a, a));
+    if length < TINY {
+        return [0.0, 0.0];
+    }
+    scale(a, 1.0 / length)
+}
@@ -0,0 +1,128 @@
+use crate::gcode::{Command, Gcode, Mcode};
+
+/// Order in which the words of a block are executed, following
+/// https://linuxcnc.org/docs/html/gcode/overview.html#gcode:order-of-execution
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum ExecutionOrder {
+    Comment,
+    Assign,
+    /// G93, G94, G95
+    FeedMode,
+    Feed,
+    /// G96, G97
+    SpindleMode,
+    Spindle,
+    ToolSelect,
+    ToolChange,
+    SpindleControl,
+    Coolant,
+    Dwell,
+    Plane,
+    Units,
+    /// G7, G8
+    DiameterMode,
+    /// G40, G41, G42
+    CutterComp,
+    /// G43, G49
+    ToolLength,
+    CoordinateSystem,
+    /// G61, G61.1, G64
+    PathControl,
+    Distance,
+    /// G98, G99
+    RetractMode,
+    /// G28, G30, G10, G92
+    HomeOrOffsets,
+    /// G53, applies to the motion of the same block
+    MachineCoordinates,
+    Motion,
+    /// M0, M1, M2, M30, M60, after the motion of their block
+    Stop,
+}
+
+impl ExecutionOrder {
+    pub fn of(command: &Command) -> Self {
+        match command {
+            Command::Comment(_) | Command::LineComment(_) | Command::ProgramDelimiter => {
+                Self::Comment
+            }
+            Command::Assign(_, _) | Command::O(_) => Self::Assign,
+            Command::F(_) => Self::Feed,
+            Command::Axes(_) | Command::ControlPoint(_) => Self::Motion,
+            Command::S(_) => Self::Spindle,
+            Command::T(_) => Self::ToolSelect,
+            Command::M(mcode) => match mcode {
+                Mcode::M6(_) => Self::ToolChange,
+                Mcode::M3 | Mcode::M4 | Mcode::M5 => Self::SpindleControl,
+                Mcode::M7 | Mcode::M8 | Mcode::M9 => Self::Coolant,
+                Mcode::M0 | Mcode::M1 | Mcode::M2 | Mcode::M30 | Mcode::M60 => Self::Stop,
+            },
+            Command::G(gcode) => match gcode {
+                Gcode::G4(_) => Self::Dwell,
+                Gcode::G17
+                | Gcode::G17_1
+                | Gcode::G18
+                | Gcode::G18_1
+                | Gcode::G19
+                | Gcode::G19_1 => Self::Plane,
+                Gcode::G20 | Gcode::G21 => Self::Units,
+                Gcode::G7 | Gcode::G8 => Self::DiameterMode,
+                Gcode::G40 | Gcode::G41(_) | Gcode::G41_1(_) | Gcode::G42(_) | Gcode::G42_1(_) => {
+                    Self::CutterComp
+                }
+                Gcode::G43(_) | Gcode::G43_1(_) | Gcode::G43_2(_) | Gcode::G49 => Self::ToolLength,
+                Gcode::G54
+                | Gcode::G55
+                | Gcode::G56
+                | Gcode::G57
+                | Gcode::G58
+                | Gcode::G59
+                | Gcode::G59_1
+                | Gcode::G59_2
+                | Gcode::G59_3 => Self::CoordinateSystem,
+                Gcode::G61 | Gcode::G61_1 | Gcode::G64(_) => Self::PathControl,
+                Gcode::G90 | Gcode::G91 => Self::Distance,
+                Gcode::G93 | Gcode::G94 | Gcode::G95 => Self::FeedMode,
+                Gcode::G96(_) | Gcode::G97 => Self::SpindleMode,
+                Gcode::G98 | Gcode::G99 => Self::RetractMode,
+                Gcode::G10(_)
+                | Gcode::G28(_)
+                | Gcode::G28_1
+                | Gcode::G30(_)
+                | Gcode::G30_1
+                | Gcode::G92(_)
+                | Gcode::G92_1
+                | Gcode::G92_2
+                | Gcode::G92_3 => Self::HomeOrOffsets,
+                Gcode::G53 => Self::MachineCoordinates,
+                Gcode::G0(_)
+                | Gcode::G1(_)
+                | Gcode::G2(_)
+                | Gcode::G3(_)
+                | Gcode::G5(_)
+                | Gcode::G5_1(_)
+                | Gcode::G5_2(_)
+                | Gcode::G5_3
+                | Gcode::G33(_)
+                | Gcode::G33_1(_)
+                | Gcode::G38_2(_)
+                | Gcode::G38_3(_)
+                | Gcode::G38_4(_)
+                | Gcode::G38_5(_)
+                | Gcode::G73(_)
+                | Gcode::G74(_)
+                | Gcode::G76(_)
+                | Gcode::G80
+                | Gcode::G81(_)
+                | Gcode::G82(_)
+                | Gcode::G83(_)
+                | Gcode::G84(_)
+                | Gcode::G85(_)
+                | Gcode::G86(_)
+                | Gcode::G87(_)
+                | Gcode::G88(_)
+                | Gcode::G89(_) => Self::Motion,
+            },
+        }
+    }
+}
@@ -1,61 +1,1774 @@
-use super::model_state::{ModelState, ModelStateUnit};
+use super::{
+    arc::{self, ArcError},
+    canned_cycle::{Cycle, CycleError, CycleKind},
+    cutter_comp::{CompError, CompPath, Segment},
+    execution_order::ExecutionOrder,
+    math::{fract, sqrt},
+    model_state::{
+        Coolant, CutterComp, DiameterMode, DistanceMode, FeedMode, MmSec, ModelState,
+        ModelStateUnit, MotionMode, PathMode, Plane, Position, RetractMode, SpindleDirection,
+        SpindleMode, Workspace,
+    },
+    motion::{ArcMove, DwellUnit, MotionEvent, Move, MoveKind},
+    params,
+    probe::{Probe, ProbeMove, ProbeTrigger},
+    program::{self, ControlError, Flow, Program},
+    spline::{self, Nurbs, SplineError},
+    threading::{Thread, ThreadError},
+    tool_table::{Tool, ToolTable},
+};
 use crate::{
     eval::{Eval as _, EvalContext},
     gcode::{
         expression::{Expression, NamedParam, Param},
-        Command, Gcode,
+        Arc, Axes, Axis, Blending, Block, CannedCycle, Command, Fcode, Gcode, Mcode, NurbsPoint,
+        Ocode, OcodeStatement, Scode, SetOffsets, Spline, SyncedMotion, Tcode, Threading,
+        ToolOffset,
     },
+    NUM_AXES,
 };
-use alloc::string::String;
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use core::time::Duration;
+
+/// Shortest `G38.x` move, as in LinuxCNC
+const PROBE_MIN_DISTANCE: f64 = 0.254;
 
 #[derive(Debug, Default)]
 pub struct Interpreter {
+    /// `#1` to `#30` of the running subroutine call, or of the main program
     local_vars_numbered: hashbrown::HashMap<u32, f32>,
     local_vars_named: hashbrown::HashMap<String, f32>,
+    global_vars_numbered: hashbrown::HashMap<u32, f32>,
     global_vars: hashbrown::HashMap<String, f32>,
+    /// local parameters of the callers of the running subroutine
+    callers: Vec<Frame>,
     model_state: ModelState,
+    /// skip blocks starting with `/`
+    block_delete: bool,
+    /// pause on `M1`
+    optional_stop: bool,
+    /// unit of the `P` word of `G4` and the canned cycles
+    dwell_unit: DwellUnit,
+    /// `G53` in the current block, the next straight move is in machine coordinates
+    machine_coordinates: bool,
+    /// `F` in the current block, which every feed move needs in inverse time mode
+    feed_in_block: bool,
+    /// parameters assigned in the current block with their values from before, which
+    /// the words of the block still read, as in LinuxCNC
+    assigned_params: Vec<(Param, Option<f32>)>,
+    /// runs `G38.x` probe moves
+    probe: Option<Box<dyn Probe>>,
+    tool_table: ToolTable,
+    /// moves held back by cutter compensation
+    comp_path: CompPath,
+    /// canned cycle words which carry over to the next cycle
+    cycle_words: CycleWords,
+    /// chord tolerance of the lines splines are split into, in mm
+    spline_tolerance: Option<f64>,
+    /// `P`, `Q` of the last motion if it was a `G5`, in mm, for a `G5` without `I`, `J`
+    spline_end_offset: Option<[f64; 2]>,
+    /// start and control points of the NURBS between `G5.2` and `G5.3`
+    nurbs: Option<(Position<NUM_AXES>, Nurbs)>,
+}
+
+/// Local parameters of a subroutine call or the main program
+#[derive(Debug, Default)]
+struct Frame {
+    numbered: hashbrown::HashMap<u32, f32>,
+    named: hashbrown::HashMap<String, f32>,
+}
+
+/// Canned cycle words, lengths in mm as programmed
+#[derive(Debug, Default, Clone, Copy)]
+struct CycleWords {
+    bottom: Option<f64>,
+    retract: Option<f64>,
+    peck: Option<f64>,
+    dwell: Option<f32>,
+    offsets: [Option<f64>; NUM_AXES],
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum InterpretError {
     ParamNotFound(Param),
     CannotEval(Expression),
+    NegativeFeedrate(f32),
+    NegativeDwell(f32),
+    NegativeSpindleSpeed(f32),
+    /// `G96` without a `D` maximum at X0, where the spindle speed is unbounded
+    SurfaceSpeedAtCenter,
+    /// feed move with an F of zero
+    ZeroFeedrate,
+    /// a required word is missing, e.g. the `L` of a `G10`
+    MissingWord(char),
+    /// unsupported `L` of a `G10`
+    InvalidL(f32),
+    /// `P` of a `G10` which is not a coordinate system
+    InvalidP(f32),
+    /// `Q` of a `G10 L1` which is not a tool orientation, 0 to 9
+    InvalidQ(f32),
+    /// `T`, `H` or `P` word which is not a tool number
+    InvalidTool(f32),
+    /// tool not in the tool table
+    UnknownTool(u32),
+    /// axis words without a motion code while no motion mode is active, e.g. after `G80`
+    NoMotionMode,
+    /// negative `P` or `Q` tolerance of a `G64`
+    NegativeTolerance(f32),
+    /// `G38.x` or a canned cycle in inverse time feed mode
+    InverseTimeFeed,
+    /// `G53` in incremental distance mode
+    G53Incremental,
+    /// `G53` without a `G0` / `G1` motion in its block
+    G53NotStraight,
+    /// `G38.x` without a [`Probe`] set on the interpreter
+    NoProbe,
+    /// `G38.x` end point within 0.254 mm (0.01 inch) of the start
+    ProbeTooClose,
+    /// `G38.2` / `G38.4` reached the end point without tripping
+    ProbeNotTripped,
+    Arc(ArcError),
+    CutterComp(CompError),
+    CannedCycle(CycleError),
+    Thread(ThreadError),
+    Spline(SplineError),
+    Control(ControlError),
+}
+
+impl From<ArcError> for InterpretError {
+    fn from(value: ArcError) -> Self {
+        InterpretError::Arc(value)
+    }
+}
+
+impl From<CycleError> for InterpretError {
+    fn from(value: CycleError) -> Self {
+        InterpretError::CannedCycle(value)
+    }
+}
+
+impl From<ControlError> for InterpretError {
+    fn from(value: ControlError) -> Self {
+        InterpretError::Control(value)
+    }
+}
+
+impl From<SplineError> for InterpretError {
+    fn from(value: SplineError) -> Self {
+        InterpretError::Spline(value)
+    }
+}
+
+impl From<ThreadError> for InterpretError {
+    fn from(value: ThreadError) -> Self {
+        InterpretError::Thread(value)
+    }
+}
+
+impl From<CompError> for InterpretError {
+    fn from(value: CompError) -> Self {
+        InterpretError::CutterComp(value)
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum InterpretValue {
     EvalExpr(f32),
+    Motion(Vec<MotionEvent>),
     Other,
 }
 
 type InterpretResult = Result<InterpretValue, InterpretError>;
+type InterpretBlockResult = Result<Vec<InterpretValue>, InterpretError>;
 
 impl Interpreter {
+    pub fn set_block_delete(&mut self, block_delete: bool) {
+        self.block_delete = block_delete;
+    }
+
+    pub fn set_optional_stop(&mut self, optional_stop: bool) {
+        self.optional_stop = optional_stop;
+    }
+
+    pub fn set_dwell_unit(&mut self, dwell_unit: DwellUnit) {
+        self.dwell_unit = dwell_unit;
+    }
+
+    pub fn set_probe(&mut self, probe: Box<dyn Probe>) {
+        self.probe = Some(probe);
+    }
+
+    /// Chord tolerance in mm of the lines `G5`, `G5.1` and `G5.2` curves are split
+    /// into, 0.0127 mm (0.0005 inch) by default
+    pub fn set_spline_tolerance(&mut self, tolerance: f64) {
+        self.spline_tolerance = Some(tolerance);
+    }
+
+    pub fn tool_table(&self) -> &ToolTable {
+        &self.tool_table
+    }
+
+    pub fn tool_table_mut(&mut self) -> &mut ToolTable {
+        &mut self.tool_table
+    }
+
+    /// Set the offset of `axis` in a coordinate system, in mm
+    pub fn set_work_offset(&mut self, workspace: Workspace, axis: Axis, offset: f64) {
+        if let Some(param) = params::work_offset(workspace, axis.to_idx()) {
+            *self.get_numbered_param_or_initialize_mut(param) = offset as f32;
+        }
+    }
+
+    pub fn interpret_block(&mut self, block: Block) -> InterpretBlockResult {
+        if block.block_delete && self.block_delete {
+            return Ok(Vec::new());
+        }
+        let mut commands = block.commands;
+        // stable sort, words of the same kind run in the order they were written
+        commands.sort_by_key(ExecutionOrder::of);
+        let values = commands
+            .into_iter()
+            .map(|command| self.interpret_command(command))
+            .collect::<InterpretBlockResult>();
+        // G53 and inverse time F words only apply to their own block
+        let machine_coordinates = core::mem::take(&mut self.machine_coordinates);
+        self.feed_in_block = false;
+        self.assigned_params.clear();
+        let values = values?;
+        if machine_coordinates {
+            return Err(InterpretError::G53NotStraight);
+        }
+        Ok(values)
+    }
+
+    /// Interprets the next block of `program` which is not an `O` word, after following
+    /// the `O` words before it, `None` at the end of the program
+    pub fn interpret_next(&mut self, program: &mut Program) -> Option<InterpretBlockResult> {
+        while program.next < program.blocks.len() {
+            let idx = program.next;
+            program.next += 1;
+            let Some(flow) = program.flows[idx] else {
+                let values = self.interpret_block(program.blocks[idx].clone());
+                if values
+                    .as_ref()
+                    .is_ok_and(|values| values.iter().any(ends_program))
+                {
+                    program.next = program.blocks.len();
+                }
+                return Some(values);
+            };
+            if let Err(error) = self.interpret_flow(program, idx, flow) {
+                return Some(Err(error));
+            }
+        }
+        None
+    }
+
+    /// Interprets every block of `program`, returns the values of all of them in order
+    pub fn interpret_program(&mut self, program: &mut Program) -> InterpretBlockResult {
+        let mut values = Vec::new();
+        while let Some(block_values) = self.interpret_next(program) {
+            values.extend(block_values?);
+        }
+        Ok(values)
+    }
+
+    /// Sets the block `program` runs next, according to the `O` word at `idx`
+    fn interpret_flow(
+        &mut self,
+        program: &mut Program,
+        idx: usize,
+        flow: Flow,
+    ) -> Result<(), InterpretError> {
+        match flow {
+            Flow::Sub { end } => program.next = end + 1,
+            Flow::Call { sub } => {
+                let arguments = match program::ocode(&program.blocks[idx]) {
+                    Some(Ocode {
+                        statement: OcodeStatement::Call(arguments),
+                        ..
+                    }) => arguments
+                        .iter()
+                        .map(|argument| self.eval_word(argument))
+                        .collect::<Result<Vec<_>, _>>()?,
+                    _ => Vec::new(),
+                };
+                self.call(arguments);
+                program.call(idx, sub);
+            }
+            // only reached in a called subroutine
+            Flow::EndSub | Flow::Return => {
+                if let Some(Ocode {
+                    statement:
+                        OcodeStatement::EndSub(Some(value)) | OcodeStatement::Return(Some(value)),
+                    ..
+                }) = program::ocode(&program.blocks[idx])
+                {
+                    let value = self.eval_word(value)?;
+                    self.global_vars.insert(params::RETURN_VALUE.into(), value);
+                    self.global_vars.insert(params::VALUE_RETURNED.into(), 1.0);
+                }
+                if program.return_from_call() {
+                    self.return_from_call();
+                }
+            }
+            Flow::If { next } => {
+                if self.eval_ocode(program, idx)? == 0.0 {
+                    program.next = self.next_branch(program, next)?;
+                }
+            }
+            // the branch before ran
+            Flow::ElseIf { end, .. } | Flow::Else { end } => program.next = end + 1,
+            Flow::EndIf | Flow::Do { .. } => {}
+            Flow::While { end } => {
+                if self.eval_ocode(program, idx)? == 0.0 {
+                    program.next = end + 1;
+                }
+            }
+            Flow::EndWhile { start } => program.next = start,
+            Flow::DoWhile { start } => {
+                if self.eval_ocode(program, idx)? != 0.0 {
+                    program.next = start + 1;
+                }
+            }
+            Flow::Repeat { end } => {
+                let count = self.eval_ocode(program, idx)?;
+                if fract(count) != 0.0 {
+                    return Err(ControlError::InvalidRepeat(count).into());
+                }
+                if count < 1.0 {
+                    program.next = end + 1;
+                } else {
+                    program.repeats.insert(idx, count as u32);
+                }
+            }
+            Flow::EndRepeat { start } => {
+                let left = program.repeats.entry(start).or_default();
+                *left = left.saturating_sub(1);
+                if *left > 0 {
+                    program.next = start + 1;
+                } else {
+                    program.repeats.remove(&start);
+                }
+            }
+            Flow::Break { start } => {
+                program.next = program.loop_end(start) + 1;
+                program.repeats.remove(&start);
+            }
+            // the end of the loop tests whether it runs again
+            Flow::Continue { start } => program.next = program.loop_end(start),
+        }
+        Ok(())
+    }
+
+    /// New frame of local parameters, with the arguments in `#1` to `#30`
+    fn call(&mut self, arguments: Vec<f32>) {
+        self.callers.push(Frame {
+            numbered: core::mem::take(&mut self.local_vars_numbered),
+            named: core::mem::take(&mut self.local_vars_named),
+        });
+        for (number, argument) in (1..).zip(arguments) {
+            self.local_vars_numbered.insert(number, argument);
+        }
+        self.global_vars.insert(params::VALUE_RETURNED.into(), 0.0);
+    }
+
+    /// Back to the local parameters of the caller
+    fn return_from_call(&mut self) {
+        if let Some(frame) = self.callers.pop() {
+            self.local_vars_numbered = frame.numbered;
+            self.local_vars_named = frame.named;
+        }
+    }
+
+    /// Block after the first of the `elseif`, `else` or `endif` from `idx` which
+    /// starts a branch to run
+    fn next_branch(&self, program: &Program, mut idx: usize) -> Result<usize, InterpretError> {
+        while let Some(Flow::ElseIf { next, .. }) = program.flows[idx] {
+            if self.eval_ocode(program, idx)? != 0.0 {
+                break;
+            }
+            idx = next;
+        }
+        Ok(idx + 1)
+    }
+
+    /// Value of the expression of the `O` word at `idx`, its condition or count
+    fn eval_ocode(&self, program: &Program, idx: usize) -> Result<f32, InterpretError> {
+        match program::ocode(&program.blocks[idx]).map(|ocode| &ocode.statement) {
+            Some(
+                OcodeStatement::If(expression)
+                | OcodeStatement::ElseIf(expression)
+                | OcodeStatement::While(expression)
+                | OcodeStatement::Repeat(expression),
+            ) => self.eval_word(expression),
+            _ => Ok(0.0),
+        }
+    }
+
+    /// Interprets a single command. A `G53` must come with its motion, in a block.
     pub fn interpret(&mut self, command: Command) -> InterpretResult {
-        match command {
-            Command::Comment(_) => todo!(),
+        let value = self.interpret_command(command);
+        self.assigned_params.clear();
+        let value = value?;
+        if core::mem::take(&mut self.machine_coordinates) {
+            return Err(InterpretError::G53NotStraight);
+        }
+        Ok(value)
+    }
+
+    fn interpret_command(&mut self, command: Command) -> InterpretResult {
+        if ExecutionOrder::of(&command) == ExecutionOrder::Motion {
+            let nurbs = matches!(
+                command,
+                Command::G(Gcode::G5_2(_) | Gcode::G5_3)
+                    | Command::Axes(_)
+                    | Command::ControlPoint(_)
+            );
+            if self.nurbs.is_some() && !nurbs {
+                return Err(SplineError::NurbsNotEnded.into());
+            }
+            if !matches!(command, Command::G(Gcode::G5(_))) {
+                self.spline_end_offset = None;
+            }
+        }
+        let value = match command {
+            Command::Comment(_) | Command::LineComment(_) => Ok(InterpretValue::Other),
+            Command::ProgramDelimiter => Ok(InterpretValue::Other),
             Command::Assign(to, from) => self.interpret_assign(to, from),
             Command::G(gcode) => self.interpret_gcode(gcode),
-            Command::M(_) => todo!(),
-            Command::O(_) => todo!(),
-            Command::S(_) => todo!(),
-            Command::T(_) => todo!(),
+            Command::M(mcode) => self.interpret_mcode(mcode),
+            Command::O(_) => Err(ControlError::NoProgram.into()),
+            Command::F(fcode) => self.interpret_fcode(fcode),
+            Command::S(scode) => self.interpret_scode(scode),
+            Command::T(tcode) => self.select_tool(tcode),
+            Command::Axes(axes) if self.nurbs.is_some() => self.add_control_point(NurbsPoint {
+                axes,
+                ..NurbsPoint::default()
+            }),
+            Command::Axes(axes) => self.interpret_modal_motion(axes),
+            Command::ControlPoint(point) => self.add_control_point(point),
+        }?;
+        self.update_spindle_rpm()?;
+        Ok(value)
+    }
+
+    fn interpret_mcode(&mut self, mcode: Mcode) -> InterpretResult {
+        match mcode {
+            Mcode::M0 | Mcode::M60 => return Ok(self.queue_event(MotionEvent::Pause)),
+            Mcode::M1 if self.optional_stop => return Ok(self.queue_event(MotionEvent::Pause)),
+            Mcode::M1 => {}
+            Mcode::M2 | Mcode::M30 => return self.end_program(),
+            Mcode::M3 => self.model_state.spindle_direction = SpindleDirection::Clockwise,
+            Mcode::M4 => self.model_state.spindle_direction = SpindleDirection::CounterClockwise,
+            Mcode::M5 => self.model_state.spindle_direction = SpindleDirection::Stopped,
+            Mcode::M6(tcode) => return self.change_tool(tcode),
+            Mcode::M7 => self.model_state.coolant.mist = true,
+            Mcode::M8 => self.model_state.coolant.flood = true,
+            Mcode::M9 => self.model_state.coolant = Coolant::default(),
         }
+        Ok(InterpretValue::Other)
+    }
+
+    /// `M2` / `M30`, resets the modes as LinuxCNC does at the end of a program
+    fn end_program(&mut self) -> InterpretResult {
+        let mut events = match self.stop_cutter_comp() {
+            InterpretValue::Motion(events) => events,
+            _ => Vec::new(),
+        };
+        for gcode in [Gcode::G92_2, Gcode::G54, Gcode::G17, Gcode::G90, Gcode::G94] {
+            self.interpret_gcode(gcode)?;
+        }
+        self.model_state.motion_mode = MotionMode::Linear;
+        self.model_state.spindle_direction = SpindleDirection::Stopped;
+        self.model_state.coolant = Coolant::default();
+        events.push(MotionEvent::End);
+        Ok(InterpretValue::Motion(events))
     }
 
     fn interpret_gcode(&mut self, gcode: Gcode) -> InterpretResult {
         match gcode {
+            Gcode::G0(None) => {
+                self.model_state.motion_mode = MotionMode::Rapid;
+                return Ok(InterpretValue::Motion(Vec::new()));
+            }
+            Gcode::G0(Some(axes)) => {
+                self.model_state.motion_mode = MotionMode::Rapid;
+                return self.interpret_straight(axes, MoveKind::Rapid);
+            }
+            Gcode::G1(axes) => {
+                self.model_state.motion_mode = MotionMode::Linear;
+                if axes.is_empty() {
+                    return Ok(InterpretValue::Motion(Vec::new()));
+                }
+                return self.interpret_straight(axes, MoveKind::Linear);
+            }
+            Gcode::G2(arc) => {
+                self.model_state.motion_mode = MotionMode::Arc { clockwise: true };
+                return self.interpret_arc(arc, true);
+            }
+            Gcode::G3(arc) => {
+                self.model_state.motion_mode = MotionMode::Arc { clockwise: false };
+                return self.interpret_arc(arc, false);
+            }
+            Gcode::G4(p) => {
+                return self.interpret_dwell(p.ok_or(InterpretError::MissingWord('P'))?)
+            }
+            Gcode::G5(spline) => return self.interpret_cubic_spline(spline),
+            Gcode::G5_1(spline) => return self.interpret_quadratic_spline(spline),
+            Gcode::G5_2(point) => return self.start_nurbs(point),
+            Gcode::G5_3 => return self.end_nurbs(),
+            Gcode::G7 => self.model_state.diameter_mode = DiameterMode::Diameter,
+            Gcode::G8 => self.model_state.diameter_mode = DiameterMode::Radius,
+            Gcode::G10(set) => self.interpret_set_offsets(set)?,
+            Gcode::G17 => self.select_plane(Plane::XY)?,
+            Gcode::G18 => self.select_plane(Plane::XZ)?,
+            Gcode::G19 => self.select_plane(Plane::YZ)?,
+            Gcode::G17_1 => self.select_plane(Plane::UV)?,
+            Gcode::G18_1 => self.select_plane(Plane::UW)?,
+            Gcode::G19_1 => self.select_plane(Plane::VW)?,
             Gcode::G20 => {
                 self.model_state.selected_unit = ModelStateUnit::In;
+                self.update_feedrate();
             }
             Gcode::G21 => {
                 self.model_state.selected_unit = ModelStateUnit::Mm;
+                self.update_feedrate();
+            }
+            Gcode::G28(axes) => return self.interpret_home(axes, params::G28_HOME),
+            Gcode::G28_1 => self.store_home(params::G28_HOME),
+            Gcode::G30(axes) => return self.interpret_home(axes, params::G30_HOME),
+            Gcode::G30_1 => self.store_home(params::G30_HOME),
+            Gcode::G33(motion) => return self.interpret_synced(motion, false),
+            Gcode::G33_1(motion) => return self.interpret_synced(motion, true),
+            Gcode::G38_2(axes) => return self.interpret_probe(axes, ProbeTrigger::Contact, true),
+            Gcode::G38_3(axes) => return self.interpret_probe(axes, ProbeTrigger::Contact, false),
+            Gcode::G38_4(axes) => {
+                return self.interpret_probe(axes, ProbeTrigger::LossOfContact, true)
+            }
+            Gcode::G38_5(axes) => {
+                return self.interpret_probe(axes, ProbeTrigger::LossOfContact, false)
+            }
+            Gcode::G40 => return Ok(self.stop_cutter_comp()),
+            Gcode::G41(d) => {
+                let diameter = self.tool_diameter(&d)?;
+                self.start_cutter_comp(CutterComp::Left, diameter)?
+            }
+            Gcode::G41_1(d) => {
+                let d = d.ok_or(InterpretError::MissingWord('D'))?;
+                let diameter = self.eval_diameter(&d)?;
+                self.start_cutter_comp(CutterComp::Left, diameter)?
             }
-            _ => todo!("{:?}", gcode),
+            Gcode::G42(d) => {
+                let diameter = self.tool_diameter(&d)?;
+                self.start_cutter_comp(CutterComp::Right, diameter)?
+            }
+            Gcode::G42_1(d) => {
+                let d = d.ok_or(InterpretError::MissingWord('D'))?;
+                let diameter = self.eval_diameter(&d)?;
+                self.start_cutter_comp(CutterComp::Right, diameter)?
+            }
+            Gcode::G43(h) => self.apply_tool_offset(h)?,
+            Gcode::G43_1(axes) => self.dynamic_tool_offset(axes)?,
+            Gcode::G43_2(offset) => self.add_tool_offset(offset)?,
+            Gcode::G49 => self.model_state.tool_offset = Position::default(),
+            Gcode::G61 => self.model_state.path_mode = PathMode::ExactPath,
+            Gcode::G61_1 => self.model_state.path_mode = PathMode::ExactStop,
+            Gcode::G64(blending) => self.interpret_blending(blending)?,
+            Gcode::G73(words) => return self.interpret_cycle(CycleKind::G73, words),
+            Gcode::G74(words) => return self.interpret_cycle(CycleKind::G74, words),
+            Gcode::G76(threading) => return self.interpret_threading(threading),
+            Gcode::G80 => self.model_state.motion_mode = MotionMode::None,
+            Gcode::G81(words) => return self.interpret_cycle(CycleKind::G81, words),
+            Gcode::G82(words) => return self.interpret_cycle(CycleKind::G82, words),
+            Gcode::G83(words) => return self.interpret_cycle(CycleKind::G83, words),
+            Gcode::G84(words) => return self.interpret_cycle(CycleKind::G84, words),
+            Gcode::G85(words) => return self.interpret_cycle(CycleKind::G85, words),
+            Gcode::G86(words) => return self.interpret_cycle(CycleKind::G86, words),
+            Gcode::G87(words) => return self.interpret_cycle(CycleKind::G87, words),
+            Gcode::G88(words) => return self.interpret_cycle(CycleKind::G88, words),
+            Gcode::G89(words) => return self.interpret_cycle(CycleKind::G89, words),
+            Gcode::G53 => self.machine_coordinates = true,
+            Gcode::G54 => self.select_workspace(Workspace::G54),
+            Gcode::G55 => self.select_workspace(Workspace::G55),
+            Gcode::G56 => self.select_workspace(Workspace::G56),
+            Gcode::G57 => self.select_workspace(Workspace::G57),
+            Gcode::G58 => self.select_workspace(Workspace::G58),
+            Gcode::G59 => self.select_workspace(Workspace::G59),
+            Gcode::G59_1 => self.select_workspace(Workspace::G59_1),
+            Gcode::G59_2 => self.select_workspace(Workspace::G59_2),
+            Gcode::G59_3 => self.select_workspace(Workspace::G59_3),
+            Gcode::G90 => self.model_state.distance_mode = DistanceMode::Absolute,
+            Gcode::G91 => self.model_state.distance_mode = DistanceMode::Incremental,
+            Gcode::G92(axes) => self.interpret_axis_offsets(axes)?,
+            Gcode::G92_1 => self.reset_axis_offsets(true),
+            Gcode::G92_2 => self.reset_axis_offsets(false),
+            Gcode::G92_3 => self.restore_axis_offsets(),
+            Gcode::G93 => self.select_feed_mode(FeedMode::InverseTime),
+            Gcode::G94 => self.select_feed_mode(FeedMode::UnitsPerMinute),
+            Gcode::G95 => self.select_feed_mode(FeedMode::UnitsPerRevolution),
+            Gcode::G96(d) => self.interpret_surface_speed(d)?,
+            Gcode::G97 => self.model_state.spindle_mode = SpindleMode::Rpm,
+            Gcode::G98 => self.model_state.retract_mode = RetractMode::OldZ,
+            Gcode::G99 => self.model_state.retract_mode = RetractMode::R,
         }
         Ok(InterpretValue::Other)
     }
 
+    /// Axis words on their own, repeating the last `G0`, `G1`, `G2`, `G3` or canned cycle
+    fn interpret_modal_motion(&mut self, axes: Axes) -> InterpretResult {
+        match self.model_state.motion_mode {
+            MotionMode::None => Err(InterpretError::NoMotionMode),
+            MotionMode::Rapid => self.interpret_straight(axes, MoveKind::Rapid),
+            MotionMode::Linear => self.interpret_straight(axes, MoveKind::Linear),
+            MotionMode::Arc { clockwise } => self.interpret_arc(
+                Arc {
+                    axes,
+                    ..Arc::default()
+                },
+                clockwise,
+            ),
+            MotionMode::CannedCycle(kind) => self.interpret_cycle(
+                kind,
+                CannedCycle {
+                    axes,
+                    ..CannedCycle::default()
+                },
+            ),
+        }
+    }
+
+    /// Canned cycle, drilling `L` holes: at the given position in absolute mode, or
+    /// each one offset from the previous one in incremental mode. `R` is relative to
+    /// the start and the bottom relative to `R` in incremental mode.
+    fn interpret_cycle(&mut self, kind: CycleKind, words: CannedCycle) -> InterpretResult {
+        if core::mem::take(&mut self.machine_coordinates) {
+            return Err(InterpretError::G53NotStraight);
+        }
+        if self.cutter_comp_on() {
+            return Err(CompError::NotAllowed.into());
+        }
+        if self.model_state.feed_mode == FeedMode::InverseTime {
+            return Err(InterpretError::InverseTimeFeed);
+        }
+        let feedrate = self.model_state.feedrate;
+        if feedrate.0 == 0.0 {
+            return Err(InterpretError::ZeroFeedrate);
+        }
+        let (first, second, normal) = self
+            .model_state
+            .plane
+            .axes()
+            .ok_or(CycleError::UnsupportedPlane)?;
+        self.model_state.motion_mode = MotionMode::CannedCycle(kind);
+
+        if let Some(z) = words.axes.get(normal) {
+            self.cycle_words.bottom = Some(self.eval_length(z)?);
+        }
+        if let Some(r) = &words.r {
+            self.cycle_words.retract = Some(self.eval_length(r)?);
+        }
+        if let Some(q) = &words.q {
+            let peck = self.eval_word(q)?;
+            if peck <= 0.0 {
+                return Err(CycleError::InvalidPeck(peck).into());
+            }
+            self.cycle_words.peck = Some(self.model_state.selected_unit.to_mm(peck as f64));
+        }
+        if let Some(p) = &words.p {
+            let dwell = self.eval_word(p)?;
+            if dwell < 0.0 {
+                return Err(InterpretError::NegativeDwell(dwell));
+            }
+            self.cycle_words.dwell = Some(dwell);
+        }
+        for (idx, expr) in words.offsets.iter() {
+            self.cycle_words.offsets[idx] = Some(self.eval_length(expr)?);
+        }
+        let repeats = match &words.l {
+            None => 1,
+            Some(l) => {
+                let repeats = self.eval_word(l)?;
+                if repeats < 1.0 || fract(repeats) != 0.0 {
+                    return Err(CycleError::InvalidRepeats(repeats).into());
+                }
+                repeats as u32
+            }
+        };
+
+        let cycle_words = self.cycle_words;
+        let normal_idx = normal.to_idx();
+        let bottom = cycle_words
+            .bottom
+            .ok_or(InterpretError::MissingWord(normal.to_chr()))?;
+        let retract = cycle_words
+            .retract
+            .ok_or(InterpretError::MissingWord('R'))?;
+        let start = self.model_state.abs_position;
+        let (retract, bottom) = match self.model_state.distance_mode {
+            DistanceMode::Absolute => {
+                let offset = self.program_offset(normal_idx);
+                (retract + offset, bottom + offset)
+            }
+            DistanceMode::Incremental => {
+                let retract = start[normal_idx] + retract;
+                (retract, retract + bottom)
+            }
+        };
+        if bottom > retract {
+            return Err(CycleError::BottomAboveR.into());
+        }
+        let clear = match self.model_state.retract_mode {
+            RetractMode::OldZ => retract.max(start[normal_idx]),
+            RetractMode::R => retract,
+        };
+
+        let peck = match cycle_words.peck {
+            Some(peck) => peck,
+            None if kind.pecks() => return Err(InterpretError::MissingWord('Q')),
+            None => 0.0,
+        };
+        let dwell = match cycle_words.dwell {
+            Some(dwell) => dwell,
+            None if kind.dwells() => return Err(InterpretError::MissingWord('P')),
+            None => 0.0,
+        };
+        let dwell = Duration::from_secs_f64(self.dwell_unit.to_secs(dwell as f64));
+        let mut offset = Position::default();
+        let mut middle = 0.0;
+        if kind == CycleKind::G87 {
+            for axis in [first, second] {
+                offset[axis.to_idx()] = cycle_words.offsets[axis.to_idx()].unwrap_or(0.0);
+            }
+            let k = cycle_words.offsets[normal_idx].ok_or(InterpretError::MissingWord('K'))?;
+            middle = match self.model_state.distance_mode {
+                DistanceMode::Absolute => k + self.program_offset(normal_idx),
+                DistanceMode::Incremental => bottom + k,
+            };
+        }
+
+        let mut hole = start;
+        let mut holes = Vec::with_capacity(repeats as usize);
+        for _ in 0..repeats {
+            for axis in [first, second] {
+                let idx = axis.to_idx();
+                if let Some(expr) = words.axes.get(axis) {
+                    let value = self.eval_coordinate(idx, expr)?;
+                    hole[idx] = match self.model_state.distance_mode {
+                        DistanceMode::Absolute => value + self.program_offset(idx),
+                        DistanceMode::Incremental => hole[idx] + value,
+                    };
+                }
+            }
+            holes.push(hole);
+        }
+
+        let cycle = Cycle {
+            kind,
+            normal: normal_idx,
+            retract,
+            bottom,
+            clear,
+            peck,
+            dwell,
+            offset,
+            middle,
+            feedrate,
+            path_mode: self.model_state.path_mode,
+        };
+        let events = cycle.expand(start, &holes);
+        hole[normal_idx] = clear;
+        self.model_state.abs_position = hole;
+        Ok(InterpretValue::Motion(events))
+    }
+
+    /// `G64`, `Q` is only read together with `P`
+    fn interpret_blending(&mut self, blending: Blending) -> Result<(), InterpretError> {
+        if blending.q.is_some() && blending.p.is_none() {
+            return Err(InterpretError::MissingWord('P'));
+        }
+        let mut tolerances = [None; 2];
+        for (tolerance, word) in tolerances.iter_mut().zip([&blending.p, &blending.q]) {
+            if let Some(word) = word {
+                let value = self.eval_word(word)?;
+                if value < 0.0 {
+                    return Err(InterpretError::NegativeTolerance(value));
+                }
+                *tolerance = Some(self.model_state.selected_unit.to_mm(value as f64));
+            }
+        }
+        let [tolerance, naive_cam_tolerance] = tolerances;
+        self.model_state.path_mode = PathMode::Blend {
+            tolerance,
+            naive_cam_tolerance,
+        };
+        Ok(())
+    }
+
+    fn select_plane(&mut self, plane: Plane) -> Result<(), InterpretError> {
+        if self.cutter_comp_on() && plane != self.model_state.plane {
+            return Err(CompError::NotAllowed.into());
+        }
+        self.model_state.plane = plane;
+        Ok(())
+    }
+
+    fn start_cutter_comp(&mut self, side: CutterComp, diameter: f64) -> Result<(), InterpretError> {
+        if self.cutter_comp_on() {
+            return Err(CompError::AlreadyOn.into());
+        }
+        let (first, second) = match (self.model_state.plane, self.model_state.plane.axes()) {
+            (Plane::XY | Plane::XZ, Some((first, second, _))) => (first.to_idx(), second.to_idx()),
+            _ => return Err(CompError::UnsupportedPlane.into()),
+        };
+        let radius = diameter / 2.0;
+        let offset = match side {
+            CutterComp::Right => -radius,
+            _ => radius,
+        };
+        self.comp_path = CompPath::new((first, second), offset);
+        self.model_state.cutter_comp = side;
+        self.model_state.cutter_comp_radius = radius;
+        Ok(())
+    }
+
+    /// `G40`, the move held back ends a tool radius from its programmed end point
+    fn stop_cutter_comp(&mut self) -> InterpretValue {
+        let events = self.comp_path.finish();
+        self.model_state.cutter_comp = CutterComp::Off;
+        self.model_state.cutter_comp_radius = 0.0;
+        if events.is_empty() {
+            InterpretValue::Other
+        } else {
+            InterpretValue::Motion(events)
+        }
+    }
+
+    /// Diameter in mm of the `D` tool of `G41` / `G42`, the current tool without `D`
+    fn tool_diameter(&self, d: &Option<Expression>) -> Result<f64, InterpretError> {
+        let number = match d {
+            Some(d) => {
+                let d = self.eval_word(d)?;
+                if d < 0.0 || fract(d) != 0.0 {
+                    return Err(CompError::InvalidD(d).into());
+                }
+                d as u32
+            }
+            None => self.model_state.tool,
+        };
+        Ok(self.tool(number)?.diameter)
+    }
+
+    /// `D` of `G41.1` / `G42.1`, in mm
+    fn eval_diameter(&self, d: &Expression) -> Result<f64, InterpretError> {
+        let diameter = self.eval_length(d)?;
+        if diameter < 0.0 {
+            return Err(CompError::InvalidD(self.eval_word(d)?).into());
+        }
+        Ok(diameter)
+    }
+
+    fn cutter_comp_on(&self) -> bool {
+        self.model_state.cutter_comp != CutterComp::Off
+    }
+
+    /// Events for a move from `from`, through cutter compensation when it is on
+    fn motion_events(
+        &mut self,
+        from: Position<NUM_AXES>,
+        mv: Move,
+    ) -> Result<Vec<MotionEvent>, InterpretError> {
+        if self.cutter_comp_on() {
+            Ok(self.comp_path.push(Segment { from, mv })?)
+        } else {
+            Ok(vec![MotionEvent::Move(mv)])
+        }
+    }
+
+    /// `T`, select the tool for the next `M6`
+    fn select_tool(&mut self, Tcode(expr): Tcode) -> InterpretResult {
+        let number = self.eval_tool_number(&expr)?;
+        self.tool(number)?;
+        self.model_state.selected_tool = number;
+        Ok(InterpretValue::Other)
+    }
+
+    /// `M6`, put the selected tool in the spindle. The tool length offset only changes
+    /// with the next `G43`.
+    fn change_tool(&mut self, tcode: Option<Tcode>) -> InterpretResult {
+        if self.cutter_comp_on() {
+            return Err(CompError::NotAllowed.into());
+        }
+        if let Some(tcode) = tcode {
+            self.select_tool(tcode)?;
+        }
+        self.model_state.tool = self.model_state.selected_tool;
+        self.store_tool_params();
+        Ok(InterpretValue::Other)
+    }
+
+    /// `G43`, use the length offsets of the `H` tool, or of the current tool
+    fn apply_tool_offset(&mut self, h: Option<Expression>) -> Result<(), InterpretError> {
+        let number = match &h {
+            Some(h) => self.eval_tool_number(h)?,
+            None => self.model_state.tool,
+        };
+        self.model_state.tool_offset = self.tool(number)?.offset;
+        Ok(())
+    }
+
+    /// `G43.1`, use the given length offsets, axes without a word get no offset
+    fn dynamic_tool_offset(&mut self, axes: Axes) -> Result<(), InterpretError> {
+        let mut offset = Position::default();
+        for (idx, expr) in axes.iter() {
+            offset[idx] = self.eval_length(expr)?;
+        }
+        self.model_state.tool_offset = offset;
+        Ok(())
+    }
+
+    /// `G43.2`, add the length offsets of the `H` tool and the axis words to the
+    /// active ones
+    fn add_tool_offset(&mut self, offset: ToolOffset) -> Result<(), InterpretError> {
+        if offset.h.is_none() && offset.axes.is_empty() {
+            return Err(InterpretError::MissingWord('H'));
+        }
+        let mut tool_offset = self.model_state.tool_offset;
+        if let Some(h) = &offset.h {
+            let tool = self.tool(self.eval_tool_number(h)?)?;
+            for idx in 0..NUM_AXES {
+                tool_offset[idx] += tool.offset[idx];
+            }
+        }
+        for (idx, expr) in offset.axes.iter() {
+            tool_offset[idx] += self.eval_length(expr)?;
+        }
+        self.model_state.tool_offset = tool_offset;
+        Ok(())
+    }
+
+    /// Data of a tool, tool 0 is no tool and needs no entry in the table
+    fn tool(&self, number: u32) -> Result<Tool, InterpretError> {
+        match self.tool_table.get(number) {
+            Some(tool) => Ok(*tool),
+            None if number == 0 => Ok(Tool::default()),
+            None => Err(InterpretError::UnknownTool(number)),
+        }
+    }
+
+    fn eval_tool_number(&self, expr: &Expression) -> Result<u32, InterpretError> {
+        let number = self.eval_word(expr)?;
+        if number < 0.0 || fract(number) != 0.0 {
+            return Err(InterpretError::InvalidTool(number));
+        }
+        Ok(number as u32)
+    }
+
+    /// Keep #5400-#5413 in sync with the tool in the spindle
+    fn store_tool_params(&mut self) {
+        let number = self.model_state.tool;
+        let tool = self.tool(number).unwrap_or_default();
+        *self.get_numbered_param_or_initialize_mut(params::TOOL) = number as f32;
+        for idx in 0..NUM_AXES {
+            *self.get_numbered_param_or_initialize_mut(params::tool_offset(idx)) =
+                tool.offset[idx] as f32;
+        }
+        for (param, value) in [
+            (params::TOOL_DIAMETER, tool.diameter),
+            (params::TOOL_FRONT_ANGLE, tool.front_angle),
+            (params::TOOL_BACK_ANGLE, tool.back_angle),
+            (params::TOOL_ORIENTATION, tool.orientation as f64),
+        ] {
+            *self.get_numbered_param_or_initialize_mut(param) = value as f32;
+        }
+    }
+
+    fn select_workspace(&mut self, workspace: Workspace) {
+        self.model_state.workspace = workspace;
+        *self.get_numbered_param_or_initialize_mut(params::COORDINATE_SYSTEM) =
+            workspace.number() as f32;
+    }
+
+    /// `G10 L2` sets the offsets of a coordinate system, `G10 L20` sets them so that
+    /// the current position has the given coordinates
+    fn interpret_set_offsets(&mut self, set: SetOffsets) -> Result<(), InterpretError> {
+        let l = self.eval_required_word('L', &set.l)?;
+        let relative_to_position = match l {
+            1.0 | 10.0 | 11.0 => return self.interpret_set_tool(set, l),
+            2.0 => false,
+            20.0 => true,
+            _ => return Err(InterpretError::InvalidL(l)),
+        };
+
+        let p = self.eval_required_word('P', &set.p)?;
+        let workspace = match p {
+            _ if fract(p) != 0.0 || p < 0.0 => None,
+            0.0 => Some(self.model_state.workspace),
+            _ => Workspace::from_number(p as u32),
+        };
+        let workspace = workspace
+            .filter(|workspace| *workspace != Workspace::Machine)
+            .ok_or(InterpretError::InvalidP(p))?;
+
+        for (idx, expr) in set.axes.iter() {
+            let offset = if relative_to_position {
+                self.model_state.abs_position[idx]
+                    - self.model_state.axis_offsets[idx]
+                    - self.model_state.tool_offset[idx]
+                    - self.eval_coordinate(idx, expr)?
+            } else {
+                self.eval_length(expr)?
+            };
+            if let Some(param) = params::work_offset(workspace, idx) {
+                *self.get_numbered_param_or_initialize_mut(param) = offset as f32;
+            }
+        }
+        Ok(())
+    }
+
+    /// `G10 L1` sets the data of the `P` tool in the tool table. `G10 L10` sets its
+    /// length offsets so that the current position has the given coordinates with the
+    /// tool, `G10 L11` does the same as if G59.3 was active without G92 offsets. The
+    /// active tool length offset only changes with the next `G43`.
+    fn interpret_set_tool(&mut self, set: SetOffsets, l: f32) -> Result<(), InterpretError> {
+        let p = self.eval_required_word('P', &set.p)?;
+        if p < 0.0 || fract(p) != 0.0 {
+            return Err(InterpretError::InvalidTool(p));
+        }
+        let number = p as u32;
+        let mut tool = *self
+            .tool_table
+            .get(number)
+            .ok_or(InterpretError::UnknownTool(number))?;
+
+        for (idx, expr) in set.axes.iter() {
+            let value = match l {
+                1.0 => self.eval_length(expr)?,
+                _ => self.eval_coordinate(idx, expr)?,
+            };
+            let position = self.model_state.abs_position[idx];
+            tool.offset[idx] = match l {
+                10.0 => {
+                    position - self.work_offset(idx) - self.model_state.axis_offsets[idx] - value
+                }
+                11.0 => position - self.workspace_offset(Workspace::G59_3, idx) - value,
+                _ => value,
+            };
+        }
+        if let Some(r) = &set.r {
+            tool.diameter = 2.0 * self.eval_length(r)?;
+        }
+        if let Some(i) = &set.i {
+            tool.front_angle = self.eval_word(i)? as f64;
+        }
+        if let Some(j) = &set.j {
+            tool.back_angle = self.eval_word(j)? as f64;
+        }
+        if let Some(q) = &set.q {
+            let q = self.eval_word(q)?;
+            if !(0.0..=9.0).contains(&q) || fract(q) != 0.0 {
+                return Err(InterpretError::InvalidQ(q));
+            }
+            tool.orientation = q as u8;
+        }
+
+        self.tool_table.set(number, tool);
+        if number == self.model_state.tool {
+            self.store_tool_params();
+        }
+        Ok(())
+    }
+
+    /// `G28` / `G30`, rapid to the intermediate point then move the axes with a word
+    /// to the home position stored from `home_params`. Without axis words every axis
+    /// goes straight home.
+    fn interpret_home(&mut self, axes: Option<Axes>, home_params: u32) -> InterpretResult {
+        if self.cutter_comp_on() {
+            return Err(CompError::NotAllowed.into());
+        }
+        let mut events = Vec::new();
+        if let Some(axes) = &axes {
+            let intermediate = self.target_position(axes)?;
+            events.push(MotionEvent::Move(Move {
+                kind: MoveKind::Rapid,
+                to: intermediate,
+                feedrate: MmSec::default(),
+                path_mode: self.model_state.path_mode,
+            }));
+            self.model_state.abs_position = intermediate;
+        }
+
+        let mut to = self.model_state.abs_position;
+        for idx in 0..NUM_AXES {
+            let homed = match &axes {
+                Some(axes) => axes.iter().any(|(axis_idx, _)| axis_idx == idx),
+                None => true,
+            };
+            if homed {
+                let home = self.get_numbered_param(home_params + idx as u32);
+                to[idx] = home.unwrap_or(0.0) as f64;
+            }
+        }
+        events.push(MotionEvent::Move(Move {
+            kind: MoveKind::Rapid,
+            to,
+            feedrate: MmSec::default(),
+            path_mode: self.model_state.path_mode,
+        }));
+        self.model_state.abs_position = to;
+        Ok(InterpretValue::Motion(events))
+    }
+
+    /// `G28.1` / `G30.1`, store the current position as the home position
+    fn store_home(&mut self, home_params: u32) {
+        for idx in 0..NUM_AXES {
+            let position = self.model_state.abs_position[idx] as f32;
+            *self.get_numbered_param_or_initialize_mut(home_params + idx as u32) = position;
+        }
+    }
+
+    /// `G92`, offset the given axes so that the current position has the given
+    /// coordinates, in every coordinate system
+    fn interpret_axis_offsets(&mut self, axes: Axes) -> Result<(), InterpretError> {
+        for (idx, expr) in axes.iter() {
+            let value = self.eval_coordinate(idx, expr)?;
+            let offset = self.model_state.abs_position[idx]
+                - self.work_offset(idx)
+                - self.model_state.tool_offset[idx]
+                - value;
+            self.model_state.axis_offsets[idx] = offset;
+        }
+        self.store_axis_offsets();
+        *self.get_numbered_param_or_initialize_mut(params::AXIS_OFFSETS_ENABLED) = 1.0;
+        Ok(())
+    }
+
+    /// `G92.1` and `G92.2`, only `G92.1` clears the parameters
+    fn reset_axis_offsets(&mut self, clear_params: bool) {
+        self.model_state.axis_offsets = Position::default();
+        if clear_params {
+            self.store_axis_offsets();
+        }
+        *self.get_numbered_param_or_initialize_mut(params::AXIS_OFFSETS_ENABLED) = 0.0;
+    }
+
+    /// `G92.3`, apply the offsets kept in the parameters
+    fn restore_axis_offsets(&mut self) {
+        for idx in 0..NUM_AXES {
+            let offset = self.get_numbered_param(params::axis_offset(idx));
+            self.model_state.axis_offsets[idx] = offset.unwrap_or(0.0) as f64;
+        }
+        *self.get_numbered_param_or_initialize_mut(params::AXIS_OFFSETS_ENABLED) = 1.0;
+    }
+
+    fn store_axis_offsets(&mut self) {
+        for idx in 0..NUM_AXES {
+            let offset = self.model_state.axis_offsets[idx] as f32;
+            *self.get_numbered_param_or_initialize_mut(params::axis_offset(idx)) = offset;
+        }
+    }
+
+    fn interpret_dwell(&mut self, p: Expression) -> InterpretResult {
+        let p = self.eval_word(&p)?;
+        if p < 0.0 {
+            return Err(InterpretError::NegativeDwell(p));
+        }
+        let duration = Duration::from_secs_f64(self.dwell_unit.to_secs(p as f64));
+        Ok(self.queue_event(MotionEvent::Dwell(duration)))
+    }
+
+    /// Event which does not move, after the moves cutter compensation holds back
+    fn queue_event(&mut self, event: MotionEvent) -> InterpretValue {
+        if self.cutter_comp_on() {
+            return InterpretValue::Motion(self.comp_path.push_event(event));
+        }
+        InterpretValue::Motion(vec![event])
+    }
+
+    /// `G0` and `G1`
+    fn interpret_straight(&mut self, axes: Axes, kind: MoveKind) -> InterpretResult {
+        let to = if core::mem::take(&mut self.machine_coordinates) {
+            if self.cutter_comp_on() {
+                return Err(CompError::NotAllowed.into());
+            }
+            self.machine_target_position(&axes)?
+        } else {
+            self.target_position(&axes)?
+        };
+        let from = self.model_state.abs_position;
+        let feedrate = match kind {
+            MoveKind::Rapid => MmSec::default(),
+            _ => {
+                let length_squared: f64 = (0..NUM_AXES)
+                    .map(|idx| (to[idx] - from[idx]) * (to[idx] - from[idx]))
+                    .sum();
+                self.feed_move_feedrate(sqrt(length_squared))?
+            }
+        };
+        let mv = Move {
+            kind,
+            to,
+            feedrate,
+            path_mode: self.model_state.path_mode,
+        };
+        let events = self.motion_events(from, mv)?;
+        self.model_state.abs_position = to;
+        Ok(InterpretValue::Motion(events))
+    }
+
+    /// Checks shared by the splines, which do not repeat with axis words
+    fn start_spline(&mut self) -> Result<(), InterpretError> {
+        if core::mem::take(&mut self.machine_coordinates) {
+            return Err(InterpretError::G53NotStraight);
+        }
+        if self.cutter_comp_on() {
+            return Err(CompError::NotAllowed.into());
+        }
+        if self.model_state.plane != Plane::XY {
+            return Err(SplineError::UnsupportedPlane.into());
+        }
+        self.model_state.motion_mode = MotionMode::None;
+        Ok(())
+    }
+
+    /// `G5`, `I` and `J` may be left out after another `G5`, continuing its curve
+    /// smoothly
+    fn interpret_cubic_spline(&mut self, spline: Spline) -> InterpretResult {
+        let previous = self.spline_end_offset.take();
+        self.start_spline()?;
+        let first = match (&spline.i, &spline.j) {
+            (Some(i), Some(j)) => [self.eval_length(i)?, self.eval_length(j)?],
+            (Some(_), None) => return Err(InterpretError::MissingWord('J')),
+            (None, Some(_)) | (None, None) if previous.is_none() => {
+                return Err(InterpretError::MissingWord('I'))
+            }
+            (None, _) => previous.map_or([0.0; 2], |[p, q]| [-p, -q]),
+        };
+        let second = [
+            self.eval_required_length('P', &spline.p)?,
+            self.eval_required_length('Q', &spline.q)?,
+        ];
+        let from = self.model_state.abs_position;
+        let to = self.target_position(&spline.axes)?;
+        let (start, end) = ([from[0], from[1]], [to[0], to[1]]);
+        let control = [
+            start,
+            [start[0] + first[0], start[1] + first[1]],
+            [end[0] + second[0], end[1] + second[1]],
+            end,
+        ];
+        let lines = spline::bezier(&control, self.spline_tolerance());
+        let value = self.spline_moves(from, to, lines)?;
+        self.spline_end_offset = Some(second);
+        Ok(value)
+    }
+
+    /// `G5.1`
+    fn interpret_quadratic_spline(&mut self, spline: Spline) -> InterpretResult {
+        self.start_spline()?;
+        let offset = [
+            self.eval_required_length('I', &spline.i)?,
+            self.eval_required_length('J', &spline.j)?,
+        ];
+        if offset == [0.0; 2] {
+            return Err(SplineError::ZeroOffsets.into());
+        }
+        let from = self.model_state.abs_position;
+        let to = self.target_position(&spline.axes)?;
+        let start = [from[0], from[1]];
+        let control = [
+            start,
+            [start[0] + offset[0], start[1] + offset[1]],
+            [to[0], to[1]],
+        ];
+        let lines = spline::bezier(&control, self.spline_tolerance());
+        self.spline_moves(from, to, lines)
+    }
+
+    /// `G5.2`, the first one starts a NURBS at the current position, of order `L`
+    fn start_nurbs(&mut self, point: NurbsPoint) -> InterpretResult {
+        if self.nurbs.is_none() {
+            self.start_spline()?;
+            let order = self.eval_optional_word(&point.l, 3.0)?;
+            if order < 2.0 || fract(order) != 0.0 {
+                return Err(SplineError::InvalidOrder(order).into());
+            }
+            let start = self.model_state.abs_position;
+            let mut nurbs = Nurbs::new(order as usize);
+            nurbs.push([start[0], start[1]], 1.0);
+            self.nurbs = Some((start, nurbs));
+        }
+        self.add_control_point(point)
+    }
+
+    /// Control point of the NURBS, which becomes the current position. The curve is
+    /// only emitted by `G5.3`.
+    fn add_control_point(&mut self, point: NurbsPoint) -> InterpretResult {
+        if self.nurbs.is_none() {
+            return Err(SplineError::NoNurbs.into());
+        }
+        let weight = self.eval_optional_word(&point.p, 1.0)?;
+        if weight <= 0.0 {
+            return Err(SplineError::InvalidWeight(weight).into());
+        }
+        let to = self.target_position(&point.axes)?;
+        if let Some((_, nurbs)) = &mut self.nurbs {
+            nurbs.push([to[0], to[1]], weight as f64);
+        }
+        self.model_state.abs_position = to;
+        Ok(InterpretValue::Motion(Vec::new()))
+    }
+
+    /// `G5.3`
+    fn end_nurbs(&mut self) -> InterpretResult {
+        let (from, nurbs) = self.nurbs.take().ok_or(SplineError::NoNurbs)?;
+        if nurbs.point_count() < nurbs.order() {
+            return Err(SplineError::TooFewPoints.into());
+        }
+        let lines = nurbs.linearize(self.spline_tolerance());
+        self.spline_moves(from, self.model_state.abs_position, lines)
+    }
+
+    /// Lines through the given XY points, the last one replaced by `to`
+    fn spline_moves(
+        &mut self,
+        from: Position<NUM_AXES>,
+        to: Position<NUM_AXES>,
+        lines: Vec<[f64; 2]>,
+    ) -> InterpretResult {
+        let mut points: Vec<Position<NUM_AXES>> = lines
+            .into_iter()
+            .map(|[x, y]| {
+                let mut point = from;
+                point[Axis::X.to_idx()] = x;
+                point[Axis::Y.to_idx()] = y;
+                point
+            })
+            .collect();
+        if let Some(last) = points.last_mut() {
+            *last = to;
+        }
+        let mut length = 0.0;
+        let mut previous = from;
+        for point in &points {
+            let length_squared: f64 = (0..NUM_AXES)
+                .map(|idx| (point[idx] - previous[idx]) * (point[idx] - previous[idx]))
+                .sum();
+            length += sqrt(length_squared);
+            previous = *point;
+        }
+        let feedrate = self.feed_move_feedrate(length)?;
+        let path_mode = self.model_state.path_mode;
+        let events = points
+            .into_iter()
+            .map(|to| {
+                MotionEvent::Move(Move {
+                    kind: MoveKind::Linear,
+                    to,
+                    feedrate,
+                    path_mode,
+                })
+            })
+            .collect();
+        self.model_state.abs_position = to;
+        Ok(InterpretValue::Motion(events))
+    }
+
+    fn spline_tolerance(&self) -> f64 {
+        self.spline_tolerance.unwrap_or(spline::DEFAULT_TOLERANCE)
+    }
+
+    /// `G33`, or `G33.1` which taps in to the end point and comes back out to the start
+    fn interpret_synced(&mut self, motion: SyncedMotion, rigid_tap: bool) -> InterpretResult {
+        if core::mem::take(&mut self.machine_coordinates) {
+            return Err(InterpretError::G53NotStraight);
+        }
+        if self.cutter_comp_on() {
+            return Err(CompError::NotAllowed.into());
+        }
+        self.model_state.motion_mode = MotionMode::None;
+        let k = self.eval_required_word('K', &motion.k)?;
+        if k <= 0.0 {
+            return Err(ThreadError::InvalidPitch(k).into());
+        }
+        let pitch = self.model_state.selected_unit.to_mm(k as f64);
+        let from = self.model_state.abs_position;
+        let to = self.target_position(&motion.axes)?;
+        let path_mode = self.model_state.path_mode;
+        if !rigid_tap {
+            self.model_state.abs_position = to;
+            return Ok(InterpretValue::Motion(vec![MotionEvent::Move(Move {
+                kind: MoveKind::Synced { pitch },
+                to,
+                feedrate: MmSec::default(),
+                path_mode,
+            })]));
+        }
+        let tap = |to| {
+            MotionEvent::Move(Move {
+                kind: MoveKind::RigidTap { pitch },
+                to,
+                feedrate: MmSec::default(),
+                path_mode,
+            })
+        };
+        Ok(InterpretValue::Motion(vec![tap(to), tap(from)]))
+    }
+
+    /// `G76`, threading along Z from the current position, which is on the drive line
+    fn interpret_threading(&mut self, words: Threading) -> InterpretResult {
+        if core::mem::take(&mut self.machine_coordinates) {
+            return Err(InterpretError::G53NotStraight);
+        }
+        if self.cutter_comp_on() {
+            return Err(CompError::NotAllowed.into());
+        }
+        self.model_state.motion_mode = MotionMode::None;
+        let p = self.eval_required_word('P', &words.p)?;
+        if p <= 0.0 {
+            return Err(ThreadError::InvalidPitch(p).into());
+        }
+        let z = words.z.ok_or(InterpretError::MissingWord('Z'))?;
+        let end_z = self.target_position(&Axes::new().set(Axis::Z, z))?[Axis::Z.to_idx()];
+        let peak = self.eval_required_word('I', &words.i)?;
+        if peak == 0.0 {
+            return Err(ThreadError::ZeroPeak.into());
+        }
+        let first_cut = self.eval_required_word('J', &words.j)?;
+        if first_cut <= 0.0 {
+            return Err(ThreadError::InvalidFirstCut(first_cut).into());
+        }
+        let depth = self.eval_required_word('K', &words.k)?;
+        if depth < first_cut {
+            return Err(ThreadError::DepthBelowFirstCut.into());
+        }
+        let degression = self.eval_optional_word(&words.r, 1.0)?;
+        if degression < 1.0 {
+            return Err(ThreadError::InvalidDegression(degression).into());
+        }
+        let compound_angle = self.eval_optional_word(&words.q, 0.0)?;
+        let spring_passes = self.eval_optional_word(&words.h, 0.0)?;
+        if spring_passes < 0.0 || fract(spring_passes) != 0.0 {
+            return Err(ThreadError::InvalidSpringPasses(spring_passes).into());
+        }
+        // LinuxCNC ignores a negative taper
+        let taper = self.eval_optional_word(&words.e, 0.0)?.max(0.0);
+        let tapers = self.eval_optional_word(&words.l, 0.0)?;
+        if !matches!(tapers, 0.0 | 1.0 | 2.0 | 3.0) {
+            return Err(ThreadError::InvalidTaper(tapers).into());
+        }
+
+        let unit = &self.model_state.selected_unit;
+        let thread = Thread {
+            end_z,
+            pitch: unit.to_mm(p as f64),
+            peak: unit.to_mm(peak as f64),
+            first_cut: unit.to_mm(first_cut as f64),
+            depth: unit.to_mm(depth as f64),
+            degression: degression as f64,
+            compound_angle: (compound_angle as f64).to_radians(),
+            spring_passes: spring_passes as u32,
+            taper: unit.to_mm(taper as f64),
+            entry_taper: tapers == 1.0 || tapers == 3.0,
+            exit_taper: tapers == 2.0 || tapers == 3.0,
+            path_mode: self.model_state.path_mode,
+        };
+        Ok(InterpretValue::Motion(
+            thread.expand(self.model_state.abs_position),
+        ))
+    }
+
+    /// `G38.x`, the probe result is stored in #5061-#5070 before an error for a probe
+    /// which had to trip, since the machine has moved either way
+    fn interpret_probe(
+        &mut self,
+        axes: Axes,
+        trigger: ProbeTrigger,
+        must_trip: bool,
+    ) -> InterpretResult {
+        if core::mem::take(&mut self.machine_coordinates) {
+            return Err(InterpretError::G53NotStraight);
+        }
+        if self.cutter_comp_on() {
+            return Err(CompError::NotAllowed.into());
+        }
+        if self.model_state.feed_mode == FeedMode::InverseTime {
+            return Err(InterpretError::InverseTimeFeed);
+        }
+        let feedrate = self.model_state.feedrate;
+        if feedrate.0 == 0.0 {
+            return Err(InterpretError::ZeroFeedrate);
+        }
+        let from = self.model_state.abs_position;
+        let to = self.target_position(&axes)?;
+        let distance_squared: f64 = (0..NUM_AXES)
+            .map(|idx| (to[idx] - from[idx]) * (to[idx] - from[idx]))
+            .sum();
+        if distance_squared < PROBE_MIN_DISTANCE * PROBE_MIN_DISTANCE {
+            return Err(InterpretError::ProbeTooClose);
+        }
+        let probe = self.probe.as_mut().ok_or(InterpretError::NoProbe)?;
+
+        let tripped = probe.probe(&ProbeMove {
+            from,
+            to,
+            feedrate,
+            trigger,
+        });
+        let stop = tripped.unwrap_or(to);
+        self.model_state.abs_position = stop;
+        for idx in 0..NUM_AXES {
+            let program = self.program_coordinate(idx, stop[idx]) as f32;
+            *self.get_numbered_param_or_initialize_mut(params::PROBE_RESULT + idx as u32) = program;
+        }
+        *self.get_numbered_param_or_initialize_mut(params::PROBE_TRIPPED) =
+            if tripped.is_some() { 1.0 } else { 0.0 };
+
+        if tripped.is_none() && must_trip {
+            return Err(InterpretError::ProbeNotTripped);
+        }
+        Ok(InterpretValue::Motion(vec![MotionEvent::Move(Move {
+            kind: MoveKind::Probe(trigger),
+            to: stop,
+            feedrate,
+            path_mode: self.model_state.path_mode,
+        })]))
+    }
+
+    fn interpret_arc(&mut self, arc: Arc, clockwise: bool) -> InterpretResult {
+        if core::mem::take(&mut self.machine_coordinates) {
+            return Err(InterpretError::G53NotStraight);
+        }
+
+        // arcs are in the selected plane, the normal axis moves helically
+        let (first, second, normal) = match self.model_state.plane.axes() {
+            Some(axes) => axes,
+            None => return Err(ArcError::UnsupportedPlane.into()),
+        };
+        if arc.offsets.get(normal).is_some() {
+            return Err(ArcError::OffsetOutOfPlane.into());
+        }
+
+        let turns = match &arc.turns {
+            None => 1,
+            Some(expr) => {
+                let turns = self.eval_word(expr)?;
+                if turns < 1.0 || fract(turns) != 0.0 {
+                    return Err(ArcError::InvalidTurns(turns).into());
+                }
+                turns as i32
+            }
+        };
+
+        let from = self.model_state.abs_position;
+        let to = self.target_position(&arc.axes)?;
+        let start = [from[first.to_idx()], from[second.to_idx()]];
+        let end = [to[first.to_idx()], to[second.to_idx()]];
+        let unit = &self.model_state.selected_unit;
+
+        let plane_center = match (&arc.radius, arc.offsets.is_empty()) {
+            (Some(_), false) => return Err(ArcError::MixedRadiusOffsets.into()),
+            (None, true) => return Err(ArcError::MissingRadiusOffsets.into()),
+            (Some(radius), true) => {
+                let radius = self.eval_length(radius)?;
+                arc::center_from_radius(start, end, radius, clockwise, unit)?
+            }
+            (None, false) => {
+                let mut offset = [0.0; 2];
+                for (idx, axis) in [first, second].into_iter().enumerate() {
+                    if let Some(expr) = arc.offsets.get(axis) {
+                        offset[idx] = self.eval_length(expr)?;
+                    }
+                }
+                arc::center_from_offsets(start, end, offset, unit)?
+            }
+        };
+
+        let mut center = from;
+        center[first.to_idx()] = plane_center[0];
+        center[second.to_idx()] = plane_center[1];
+        let turns = if clockwise { -turns } else { turns };
+        let normal_idx = normal.to_idx();
+        let length = arc::length(
+            start,
+            end,
+            plane_center,
+            turns,
+            to[normal_idx] - from[normal_idx],
+        );
+        let feedrate = self.feed_move_feedrate(length)?;
+
+        let mv = Move {
+            kind: MoveKind::Arc(ArcMove { center, turns }),
+            to,
+            feedrate,
+            path_mode: self.model_state.path_mode,
+        };
+        let events = self.motion_events(from, mv)?;
+        self.model_state.abs_position = to;
+        Ok(InterpretValue::Motion(events))
+    }
+
+    /// End point of a move in machine coordinates, axes without a word keep their
+    /// current position
+    fn target_position(&self, axes: &Axes) -> Result<Position<NUM_AXES>, InterpretError> {
+        let mut position = self.model_state.abs_position;
+        for (idx, expr) in axes.iter() {
+            let value = self.eval_coordinate(idx, expr)?;
+            position[idx] = match self.model_state.distance_mode {
+                DistanceMode::Absolute => value + self.program_offset(idx),
+                DistanceMode::Incremental => position[idx] + value,
+            };
+        }
+        Ok(position)
+    }
+
+    /// End point of a `G53` move, axis words are machine coordinates
+    fn machine_target_position(&self, axes: &Axes) -> Result<Position<NUM_AXES>, InterpretError> {
+        if self.model_state.distance_mode == DistanceMode::Incremental {
+            return Err(InterpretError::G53Incremental);
+        }
+        let mut position = self.model_state.abs_position;
+        for (idx, expr) in axes.iter() {
+            position[idx] = self.eval_coordinate(idx, expr)?;
+        }
+        Ok(position)
+    }
+
+    /// Coordinate of a machine position in the active coordinate system, in the
+    /// selected unit
+    fn program_coordinate(&self, axis_idx: usize, machine: f64) -> f64 {
+        let offset = self.program_offset(axis_idx);
+        self.model_state.selected_unit.mm_to_unit(machine - offset)
+    }
+
+    /// Offset in mm of program coordinates from machine coordinates: the coordinate
+    /// system, G92 and tool length offsets
+    fn program_offset(&self, axis_idx: usize) -> f64 {
+        self.work_offset(axis_idx)
+            + self.model_state.axis_offsets[axis_idx]
+            + self.model_state.tool_offset[axis_idx]
+    }
+
+    /// Offset in mm of the active coordinate system from machine coordinates
+    fn work_offset(&self, axis_idx: usize) -> f64 {
+        self.workspace_offset(self.model_state.workspace, axis_idx)
+    }
+
+    fn workspace_offset(&self, workspace: Workspace, axis_idx: usize) -> f64 {
+        params::work_offset(workspace, axis_idx)
+            .and_then(|param| self.get_numbered_param(param))
+            .unwrap_or(0.0) as f64
+    }
+
+    fn interpret_fcode(&mut self, Fcode(expr): Fcode) -> InterpretResult {
+        let feedrate = match self.eval_expr(&expr) {
+            Some(val) => val,
+            None => return Err(InterpretError::CannotEval(expr)),
+        };
+        if feedrate < 0.0 {
+            return Err(InterpretError::NegativeFeedrate(feedrate));
+        }
+        self.model_state.programmed_feedrate = feedrate as f64;
+        self.feed_in_block = true;
+        self.update_feedrate();
+        Ok(InterpretValue::Other)
+    }
+
+    fn interpret_scode(&mut self, Scode(expr): Scode) -> InterpretResult {
+        let speed = self.eval_word(&expr)?;
+        if speed < 0.0 {
+            return Err(InterpretError::NegativeSpindleSpeed(speed));
+        }
+        self.model_state.spindle_speed = speed as f64;
+        Ok(InterpretValue::Other)
+    }
+
+    /// `G96`, the spindle speed follows the X position from now on
+    fn interpret_surface_speed(&mut self, d: Option<Expression>) -> Result<(), InterpretError> {
+        let max_rpm = match &d {
+            Some(d) => {
+                let max_rpm = self.eval_word(d)?;
+                if max_rpm < 0.0 {
+                    return Err(InterpretError::NegativeSpindleSpeed(max_rpm));
+                }
+                Some(max_rpm as f64)
+            }
+            None => None,
+        };
+        self.model_state.spindle_mode = SpindleMode::ConstantSurfaceSpeed { max_rpm };
+        Ok(())
+    }
+
+    /// Recompute the commanded spindle speed, and with it the feedrate in units per
+    /// revolution mode. In constant surface speed mode it follows the distance of the
+    /// current position from X0.
+    fn update_spindle_rpm(&mut self) -> Result<(), InterpretError> {
+        let speed = self.model_state.spindle_speed;
+        let rpm = match self.model_state.spindle_mode {
+            SpindleMode::Rpm => speed,
+            SpindleMode::ConstantSurfaceSpeed { .. } if speed == 0.0 => 0.0,
+            SpindleMode::ConstantSurfaceSpeed { max_rpm } => {
+                // S is in feet or meters per minute
+                let mm_per_minute = match self.model_state.selected_unit {
+                    ModelStateUnit::In => speed * 12.0 * 25.4,
+                    ModelStateUnit::Mm => speed * 1000.0,
+                };
+                let x = Axis::X.to_idx();
+                let radius = (self.model_state.abs_position[x] - self.program_offset(x)).abs();
+                let rpm = mm_per_minute / (core::f64::consts::TAU * radius);
+                match max_rpm {
+                    Some(max_rpm) => rpm.min(max_rpm),
+                    None if radius == 0.0 => return Err(InterpretError::SurfaceSpeedAtCenter),
+                    None => rpm,
+                }
+            }
+        };
+        self.model_state.spindle_rpm = rpm;
+        self.update_feedrate();
+        Ok(())
+    }
+
+    fn select_feed_mode(&mut self, feed_mode: FeedMode) {
+        self.model_state.feed_mode = feed_mode;
+        self.update_feedrate();
+    }
+
+    /// Recompute the normalized feedrate from the programmed F word, the selected
+    /// unit, the feed mode and the spindle speed
+    fn update_feedrate(&mut self) {
+        let model_state = &mut self.model_state;
+        let per_minute = match model_state.feed_mode {
+            // depends on the length of the move, see `feed_move_feedrate`
+            FeedMode::InverseTime => 0.0,
+            FeedMode::UnitsPerMinute => model_state.programmed_feedrate,
+            FeedMode::UnitsPerRevolution => {
+                model_state.programmed_feedrate * model_state.spindle_rpm
+            }
+        };
+        model_state.feedrate = MmSec(model_state.selected_unit.to_mm(per_minute) / 60.0);
+    }
+
+    /// Feedrate of a `G1`, `G2` or `G3` move `length` mm long. In inverse time mode
+    /// the move takes a minute divided by the `F` word of its own block.
+    fn feed_move_feedrate(&mut self, length: f64) -> Result<MmSec, InterpretError> {
+        if self.model_state.feed_mode != FeedMode::InverseTime {
+            let feedrate = self.model_state.feedrate;
+            if feedrate.0 == 0.0 {
+                return Err(InterpretError::ZeroFeedrate);
+            }
+            return Ok(feedrate);
+        }
+        if !core::mem::take(&mut self.feed_in_block) {
+            return Err(InterpretError::MissingWord('F'));
+        }
+        let moves_per_minute = self.model_state.programmed_feedrate;
+        if moves_per_minute == 0.0 {
+            return Err(InterpretError::ZeroFeedrate);
+        }
+        Ok(MmSec(length * moves_per_minute / 60.0))
+    }
+
     pub fn get_model_state(&self) -> &ModelState {
         &self.model_state
     }
@@ -65,6 +1778,21 @@ impl Interpreter {
             Some(val) => val,
             None => return Err(InterpretError::CannotEval(from)),
         };
+        let to = match to {
+            Param::Expr(expr) => match self.eval_expr(&expr) {
+                Some(num) => Param::Numbered(num as u32),
+                None => return Err(InterpretError::ParamNotFound(Param::Expr(expr))),
+            },
+            to => to,
+        };
+        if !self
+            .assigned_params
+            .iter()
+            .any(|(assigned, _)| *assigned == to)
+        {
+            let before = self.get_param(&to);
+            self.assigned_params.push((to.clone(), before));
+        }
 
         let to = self
             .get_param_or_initialize_mut(&to)
@@ -103,7 +1831,12 @@ impl Interpreter {
     }
 
     fn get_numbered_param_or_initialize_mut(&mut self, param_num: u32) -> &mut f32 {
-        self.local_vars_numbered.entry(param_num).or_insert(0.0)
+        let vars = if params::is_local(param_num) {
+            &mut self.local_vars_numbered
+        } else {
+            &mut self.global_vars_numbered
+        };
+        vars.entry(param_num).or_insert(0.0)
     }
 
     fn get_param(&self, param: &Param) -> Option<f32> {
@@ -128,12 +1861,73 @@ impl Interpreter {
         self.global_vars.get(name).copied()
     }
     pub fn get_numbered_param(&self, name: u32) -> Option<f32> {
-        self.local_vars_numbered.get(&name).copied()
+        let vars = if params::is_local(name) {
+            &self.local_vars_numbered
+        } else {
+            &self.global_vars_numbered
+        };
+        vars.get(&name).copied()
     }
 
     fn eval_expr(&self, expression: &Expression) -> Option<f32> {
         expression.eval(self)
     }
+
+    /// Value of a word, e.g. the `10` in `X10`
+    fn eval_word(&self, expression: &Expression) -> Result<f32, InterpretError> {
+        self.eval_expr(expression)
+            .ok_or_else(|| InterpretError::CannotEval(expression.clone()))
+    }
+
+    fn eval_optional_word(
+        &self,
+        expression: &Option<Expression>,
+        default: f32,
+    ) -> Result<f32, InterpretError> {
+        match expression {
+            Some(expression) => self.eval_word(expression),
+            None => Ok(default),
+        }
+    }
+
+    fn eval_required_length(
+        &self,
+        letter: char,
+        expression: &Option<Expression>,
+    ) -> Result<f64, InterpretError> {
+        let value = self.eval_required_word(letter, expression)?;
+        Ok(self.model_state.selected_unit.to_mm(value as f64))
+    }
+
+    fn eval_required_word(
+        &self,
+        letter: char,
+        expression: &Option<Expression>,
+    ) -> Result<f32, InterpretError> {
+        match expression {
+            Some(expression) => self.eval_word(expression),
+            None => Err(InterpretError::MissingWord(letter)),
+        }
+    }
+
+    /// Value of a length word in mm, converted from the selected unit
+    fn eval_length(&self, expression: &Expression) -> Result<f64, InterpretError> {
+        let value = self.eval_word(expression)?;
+        Ok(self.model_state.selected_unit.to_mm(value as f64))
+    }
+
+    /// Axis word of a position in mm, X words are diameters in lathe diameter mode
+    fn eval_coordinate(
+        &self,
+        axis_idx: usize,
+        expression: &Expression,
+    ) -> Result<f64, InterpretError> {
+        let value = self.eval_length(expression)?;
+        match self.model_state.diameter_mode {
+            DiameterMode::Diameter if axis_idx == Axis::X.to_idx() => Ok(value / 2.0),
+            _ => Ok(value),
+        }
+    }
 }
 
 impl EvalContext for Interpreter {
@@ -142,7 +1936,23 @@ impl EvalContext for Interpreter {
     }
 
     fn get_param(&self, param: &Param) -> Option<f32> {
-        self.get_param(param)
+        let numbered;
+        let param = match param {
+            Param::Expr(expr) => {
+                numbered = Param::Numbered(self.eval_expr(expr)? as u32);
+                &numbered
+            }
+            param => param,
+        };
+        // the words of a block read the parameters from before its assignments
+        match self
+            .assigned_params
+            .iter()
+            .find(|(assigned, _)| assigned == param)
+        {
+            Some((_, before)) => *before,
+            None => self.get_param(param),
+        }
     }
 
     fn named_param_exists(&self, param: &NamedParam) -> bool {
@@ -157,6 +1967,14 @@ impl EvalContext for Interpreter {
     }
 }
 
+/// Whether the value is of an `M2` or `M30`
+fn ends_program(value: &InterpretValue) -> bool {
+    match value {
+        InterpretValue::Motion(events) => events.contains(&MotionEvent::End),
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod test {
     extern crate std;
@@ -0,0 +1,101 @@
+//! f64 math for no_std builds, from libm. micromath only approximates f32 functions,
+//! e.g. `sqrt` to within ~5%, which is far too coarse for toolpath geometry.
+
+pub use libm::{atan2, pow as powf, sqrt, tan};
+
+/// Fractional part of a word's value, as `f32::fract` which needs std
+pub fn fract(value: f32) -> f32 {
+    value - libm::truncf(value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{atan2, fract, powf, sqrt, tan};
+
+    #[test]
+    fn test_sqrt() {
+        for value in [0.0, 1e-12, 0.5, 2.0, 6.25, 42.25, 123.456, 1e12, 1e40] {
+            let root = sqrt(value);
+            assert!(
+                (root * root - value).abs() <= value * 1e-15,
+                "sqrt({}) = {}",
+                value,
+                root
+            );
+        }
+        assert!(sqrt(-1.0).is_nan());
+    }
+
+    #[test]
+    fn test_atan2() {
+        use core::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+        for (y, x, expected) in [
+            (0.0, 1.0, 0.0),
+            (1.0, 1.0, FRAC_PI_4),
+            (1.0, 0.0, FRAC_PI_2),
+            (1.0, -1.0, 3.0 * FRAC_PI_4),
+            (1e-9, -1.0, PI - 1e-9),
+            (-1.0, -1.0, -3.0 * FRAC_PI_4),
+            (-1.0, 0.0, -FRAC_PI_2),
+            (-3.0, 4.0, -0.6435011087932844),
+            (0.5, 0.25, 1.1071487177940904),
+        ] {
+            let angle = atan2(y, x);
+            assert!(
+                (angle - expected).abs() <= 1e-15,
+                "atan2({}, {}) = {}",
+                y,
+                x,
+                angle
+            );
+        }
+    }
+
+    #[test]
+    fn test_powf() {
+        for (base, exponent, expected) in [
+            (2.0, 0.5, core::f64::consts::SQRT_2),
+            (9.0, 0.5, 3.0),
+            (3.0, 1.0, 3.0),
+            (5.0, 1.0 / 1.5, 2.924017738212866),
+            (0.01, 2.0, 1e-4),
+            (1e6, 1.0 / 3.0, 100.0),
+        ] {
+            let value = powf(base, exponent);
+            assert!(
+                (value - expected).abs() <= expected * 1e-14,
+                "powf({}, {}) = {}",
+                base,
+                exponent,
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_tan() {
+        for (angle, expected) in [
+            (0.0, 0.0),
+            (core::f64::consts::FRAC_PI_4, 1.0),
+            (-core::f64::consts::FRAC_PI_6, -0.5773502691896257),
+            (29.5f64.to_radians(), 0.5657727781877702),
+            (3.0, -0.1425465430742778),
+        ] {
+            let value = tan(angle);
+            assert!(
+                (value - expected).abs() <= 1e-12,
+                "tan({}) = {}",
+                angle,
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_fract() {
+        for (value, expected) in [(2.0, 0.0), (2.5, 0.5), (-2.5, -0.5), (-0.0, 0.0)] {
+            assert_eq!(fract(value), expected, "fract({})", value);
+        }
+        assert!(fract(f32::INFINITY).is_nan());
+    }
+}
@@ -1,8 +1,33 @@
+mod arc;
+mod canned_cycle;
+mod cutter_comp;
+mod execution_order;
 mod interpreter;
+mod math;
 mod model_state;
+mod motion;
+mod params;
+mod probe;
+mod program;
+mod spline;
+mod threading;
+mod tool_table;
 
 #[cfg(test)]
 mod test;
 
+pub use canned_cycle::{CycleError, CycleKind};
+pub use cutter_comp::CompError;
 pub use interpreter::InterpretError;
+pub use interpreter::InterpretValue;
 pub use interpreter::Interpreter;
+pub use model_state::{
+    Coolant, CutterComp, DiameterMode, FeedMode, MmSec, MotionMode, PathMode, Position,
+    RetractMode, SpindleDirection, SpindleMode, Workspace,
+};
+pub use motion::{ArcMove, DwellUnit, MotionEvent, Move, MoveKind};
+pub use probe::{Probe, ProbeMove, ProbeTrigger};
+pub use program::{ControlError, Program};
+pub use spline::SplineError;
+pub use threading::ThreadError;
+pub use tool_table::{TblError, Tool, ToolTable};
@@ -1,27 +1,217 @@
-use core::fmt::Debug;
+use core::{
+    fmt::Debug,
+    ops::{Index, IndexMut},
+};
 
-use crate::NUM_AXES;
+use super::canned_cycle::CycleKind;
+use crate::{gcode::Axis, NUM_AXES};
 
 #[derive(Debug, Default)]
 pub struct ModelState {
     pub selected_unit: ModelStateUnit,
+    pub plane: Plane,
+    pub distance_mode: DistanceMode,
+    pub diameter_mode: DiameterMode,
+    /// motion repeated by axis words without a motion code
+    pub motion_mode: MotionMode,
+    pub retract_mode: RetractMode,
+    pub path_mode: PathMode,
+    pub feed_mode: FeedMode,
+    /// F word as programmed: in the selected unit per minute or per revolution, or
+    /// moves per minute in inverse time mode
+    pub programmed_feedrate: f64,
+    /// programmed feedrate normalized to the selected unit and feed mode, zero in
+    /// inverse time mode where it depends on the length of each move
     pub feedrate: MmSec,
+    pub spindle_mode: SpindleMode,
+    /// S word as programmed: revolutions per minute, or the surface speed in feet or
+    /// meters per minute in constant surface speed mode
+    pub spindle_speed: f64,
+    /// commanded spindle speed in revolutions per minute, following the X position in
+    /// constant surface speed mode
+    pub spindle_rpm: f64,
+    pub spindle_direction: SpindleDirection,
+    pub coolant: Coolant,
     pub workspace: Workspace,
+    /// G92 offsets in mm, applied on top of the offset of the coordinate system
+    pub axis_offsets: Position<NUM_AXES>,
+    /// tool in the spindle, 0 for none
+    pub tool: u32,
+    /// tool selected by `T`, put in the spindle by `M6`
+    pub selected_tool: u32,
+    /// tool length offsets in mm, applied on top of the axis offsets
+    pub tool_offset: Position<NUM_AXES>,
+    pub cutter_comp: CutterComp,
+    /// tool radius used by cutter compensation, in mm
+    pub cutter_comp_radius: f64,
+    /// programmed position, without cutter compensation
     pub abs_position: Position<NUM_AXES>,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
 pub struct MmSec(pub f64);
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub struct Position<const N: usize>([f64; N]);
+impl<const N: usize> Position<N> {
+    pub fn new(values: [f64; N]) -> Self {
+        Self(values)
+    }
+}
 impl<const N: usize> Default for Position<N> {
     fn default() -> Self {
         Self([0.0; N])
     }
 }
+impl<const N: usize> Index<usize> for Position<N> {
+    type Output = f64;
+    fn index(&self, idx: usize) -> &f64 {
+        &self.0[idx]
+    }
+}
+impl<const N: usize> IndexMut<usize> for Position<N> {
+    fn index_mut(&mut self, idx: usize) -> &mut f64 {
+        &mut self.0[idx]
+    }
+}
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum SpindleDirection {
+    /// M5
+    #[default]
+    Stopped,
+    /// M3
+    Clockwise,
+    /// M4
+    CounterClockwise,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct Coolant {
+    /// M7
+    pub mist: bool,
+    /// M8
+    pub flood: bool,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum Plane {
+    /// G17
+    #[default]
+    XY,
+    /// G18
+    XZ,
+    /// G19
+    YZ,
+    /// G17.1
+    UV,
+    /// G18.1
+    UW,
+    /// G19.1
+    VW,
+}
+
+impl Plane {
+    /// (first, second, normal) axes of the plane, ordered so that counter-clockwise
+    /// is counter-clockwise looking down the normal axis. `None` for the UVW planes.
+    pub fn axes(self) -> Option<(Axis, Axis, Axis)> {
+        match self {
+            Plane::XY => Some((Axis::X, Axis::Y, Axis::Z)),
+            Plane::XZ => Some((Axis::Z, Axis::X, Axis::Y)),
+            Plane::YZ => Some((Axis::Y, Axis::Z, Axis::X)),
+            Plane::UV | Plane::UW | Plane::VW => None,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum DistanceMode {
+    /// G90, axis words are coordinates
+    #[default]
+    Absolute,
+    /// G91, axis words are distances from the current position
+    Incremental,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum DiameterMode {
+    /// G8, X words are radii
+    #[default]
+    Radius,
+    /// G7, X words are diameters, positions stay radii
+    Diameter,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum SpindleMode {
+    /// G97, S is in revolutions per minute
+    #[default]
+    Rpm,
+    /// G96, S is the surface speed at the X position, with X0 on the spindle axis.
+    /// Without a maximum the speed is unbounded at X0.
+    ConstantSurfaceSpeed { max_rpm: Option<f64> },
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum MotionMode {
+    /// G80, no motion yet, or G5.x, G33, G33.1, G76, which do not repeat
+    #[default]
+    None,
+    /// G0
+    Rapid,
+    /// G1
+    Linear,
+    /// G2 / G3
+    Arc { clockwise: bool },
+    /// G73, G74, G81 to G89
+    CannedCycle(CycleKind),
+}
+
+/// How the machine may move through the corners between moves
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PathMode {
+    /// G61, follow the programmed path exactly
+    ExactPath,
+    /// G61.1, stop at the end of every move
+    ExactStop,
+    /// G64, blend corners, optionally staying within `tolerance` of the path and
+    /// merging moves which are within `naive_cam_tolerance` of a straight line, in mm
+    Blend {
+        tolerance: Option<f64>,
+        naive_cam_tolerance: Option<f64>,
+    },
+}
+
+impl Default for PathMode {
+    fn default() -> Self {
+        PathMode::Blend {
+            tolerance: None,
+            naive_cam_tolerance: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum RetractMode {
+    /// G98, back to the level the cycle started at, or `R` if it is higher
+    #[default]
+    OldZ,
+    /// G99, back to `R`
+    R,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum CutterComp {
+    /// G40
+    #[default]
+    Off,
+    /// G41 / G41.1, tool to the left of the path
+    Left,
+    /// G42 / G42.1, tool to the right of the path
+    Right,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
 pub enum Workspace {
     #[default]
     Machine,
@@ -36,9 +226,69 @@ pub enum Workspace {
     G59_3,
 }
 
+impl Workspace {
+    /// LinuxCNC coordinate system number, 1 (G54) to 9 (G59.3), 0 for machine
+    pub fn number(self) -> u32 {
+        match self {
+            Workspace::Machine => 0,
+            Workspace::G54 => 1,
+            Workspace::G55 => 2,
+            Workspace::G56 => 3,
+            Workspace::G57 => 4,
+            Workspace::G58 => 5,
+            Workspace::G59 => 6,
+            Workspace::G59_1 => 7,
+            Workspace::G59_2 => 8,
+            Workspace::G59_3 => 9,
+        }
+    }
+
+    /// Coordinate system with LinuxCNC number 1 (G54) to 9 (G59.3)
+    pub fn from_number(number: u32) -> Option<Self> {
+        match number {
+            1 => Some(Workspace::G54),
+            2 => Some(Workspace::G55),
+            3 => Some(Workspace::G56),
+            4 => Some(Workspace::G57),
+            5 => Some(Workspace::G58),
+            6 => Some(Workspace::G59),
+            7 => Some(Workspace::G59_1),
+            8 => Some(Workspace::G59_2),
+            9 => Some(Workspace::G59_3),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Default, PartialEq)]
 pub enum ModelStateUnit {
     In,
     #[default]
     Mm,
 }
+
+impl ModelStateUnit {
+    pub fn to_mm(&self, value: f64) -> f64 {
+        match self {
+            ModelStateUnit::In => value * 25.4,
+            ModelStateUnit::Mm => value,
+        }
+    }
+    pub fn mm_to_unit(&self, value: f64) -> f64 {
+        match self {
+            ModelStateUnit::In => value / 25.4,
+            ModelStateUnit::Mm => value,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum FeedMode {
+    /// G93, F is the number of moves per minute and is required on every feed move
+    InverseTime,
+    /// G94, F is in units per minute
+    #[default]
+    UnitsPerMinute,
+    /// G95, F is in units per spindle revolution
+    UnitsPerRevolution,
+}
@@ -0,0 +1,75 @@
+use super::{
+    model_state::{MmSec, PathMode, Position},
+    probe::ProbeTrigger,
+};
+use crate::NUM_AXES;
+use core::time::Duration;
+
+/// A single thing for the machine to do, produced by a motion command
+#[derive(Debug, PartialEq, Clone)]
+pub enum MotionEvent {
+    Move(Move),
+    /// wait, without moving
+    Dwell(Duration),
+    /// wait for the operator to resume the program
+    Pause,
+    /// end of the program
+    End,
+}
+
+/// How the `P` word of `G4` is read. LinuxCNC and GRBL use seconds, Marlin uses
+/// milliseconds.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum DwellUnit {
+    #[default]
+    Seconds,
+    Milliseconds,
+}
+
+impl DwellUnit {
+    pub fn to_secs(self, value: f64) -> f64 {
+        match self {
+            DwellUnit::Seconds => value,
+            DwellUnit::Milliseconds => value / 1000.0,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Move {
+    pub kind: MoveKind,
+    /// end point, in machine coordinates
+    pub to: Position<NUM_AXES>,
+    /// zero for rapids, which move at the machine's rapid rate, and for spindle
+    /// synchronized moves, which follow the spindle
+    pub feedrate: MmSec,
+    /// path control mode the move was programmed in, for the corner at its end
+    pub path_mode: PathMode,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum MoveKind {
+    /// `G0`
+    Rapid,
+    /// `G1`
+    Linear,
+    /// `G2` / `G3`
+    Arc(ArcMove),
+    /// `G33` and the passes of `G76`, travelling `pitch` mm along Z per spindle
+    /// revolution
+    Synced { pitch: f64 },
+    /// `G33.1`, in pairs: in to the bottom of the hole, then back out to the start with
+    /// the spindle reversed
+    RigidTap { pitch: f64 },
+    /// `G38.x`, already run by the [`Probe`](super::probe::Probe), ending where the
+    /// probe stopped
+    Probe(ProbeTrigger),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ArcMove {
+    /// center in machine coordinates, the axis normal to the plane keeps its start value
+    pub center: Position<NUM_AXES>,
+    /// signed number of turns, positive is counter-clockwise
+    pub turns: i32,
+}
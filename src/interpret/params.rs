@@ -0,0 +1,67 @@
+//! Parameters with a meaning to the interpreter, following
+//! https://linuxcnc.org/docs/html/gcode/overview.html#sub:numbered-parameters
+//!
+//! Lengths stored in these parameters are in mm, like machine positions, except
+//! for the probe results which are read by programs.
+
+use super::model_state::Workspace;
+
+/// `#1` to `#30` are local to a subroutine call, which sets them to its arguments
+pub const LAST_LOCAL: u32 = 30;
+/// Value returned by the last subroutine call
+pub const RETURN_VALUE: &str = "_value";
+/// 1 if the last subroutine call returned a value, 0 otherwise
+pub const VALUE_RETURNED: &str = "_value_returned";
+/// X of the last probe result in program coordinates and units, followed by the
+/// other axes
+pub const PROBE_RESULT: u32 = 5061;
+/// 1 if the last probe tripped, 0 otherwise
+pub const PROBE_TRIPPED: u32 = 5070;
+/// X of the `G28` home position in machine coordinates, followed by the other axes
+pub const G28_HOME: u32 = 5161;
+/// X of the `G30` home position in machine coordinates, followed by the other axes
+pub const G30_HOME: u32 = 5181;
+/// 1 while the G92 axis offsets are applied, 0 otherwise
+pub const AXIS_OFFSETS_ENABLED: u32 = 5210;
+/// X axis offset of G92, followed by the other axes
+const AXIS_OFFSET_BASE: u32 = 5211;
+/// Active coordinate system, 1 (G54) to 9 (G59.3), 0 for machine coordinates
+pub const COORDINATE_SYSTEM: u32 = 5220;
+/// X offset of coordinate system 1 (G54)
+const WORK_OFFSET_BASE: u32 = 5221;
+/// Parameters between the same axis of consecutive coordinate systems
+const WORK_OFFSET_STRIDE: u32 = 20;
+/// Number of the tool in the spindle, 0 for none
+pub const TOOL: u32 = 5400;
+/// X length offset of the tool in the spindle, followed by the other axes
+const TOOL_OFFSET_BASE: u32 = 5401;
+/// Diameter, front angle, back angle and orientation of the tool in the spindle
+pub const TOOL_DIAMETER: u32 = 5410;
+pub const TOOL_FRONT_ANGLE: u32 = 5411;
+pub const TOOL_BACK_ANGLE: u32 = 5412;
+pub const TOOL_ORIENTATION: u32 = 5413;
+
+/// Whether the numbered parameter is local to a subroutine call
+pub fn is_local(number: u32) -> bool {
+    (1..=LAST_LOCAL).contains(&number)
+}
+
+/// Parameter holding the offset of `axis_idx` for `workspace`, `None` for machine
+/// coordinates which have no offset
+pub fn work_offset(workspace: Workspace, axis_idx: usize) -> Option<u32> {
+    let number = workspace.number();
+    if number == 0 {
+        return None;
+    }
+    Some(WORK_OFFSET_BASE + (number - 1) * WORK_OFFSET_STRIDE + axis_idx as u32)
+}
+
+/// Parameter holding the G92 offset of `axis_idx`
+pub fn axis_offset(axis_idx: usize) -> u32 {
+    AXIS_OFFSET_BASE + axis_idx as u32
+}
+
+/// Parameter holding the length offset of `axis_idx` of the tool in the spindle
+pub fn tool_offset(axis_idx: usize) -> u32 {
+    TOOL_OFFSET_BASE + axis_idx as u32
+}
@@ -0,0 +1,28 @@
+use super::model_state::{MmSec, Position};
+use crate::NUM_AXES;
+use core::fmt::Debug;
+
+/// What stops a `G38.x` probe move
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ProbeTrigger {
+    /// `G38.2` / `G38.3`, moving toward the workpiece
+    Contact,
+    /// `G38.4` / `G38.5`, moving away from the workpiece
+    LossOfContact,
+}
+
+/// A straight probe move, in machine coordinates
+#[derive(Debug, PartialEq, Clone)]
+pub struct ProbeMove {
+    pub from: Position<NUM_AXES>,
+    pub to: Position<NUM_AXES>,
+    pub feedrate: MmSec,
+    pub trigger: ProbeTrigger,
+}
+
+/// Runs probe moves for the interpreter, e.g. the machine or a simulation
+pub trait Probe: Debug {
+    /// Move until `mv.trigger` happens, returning the machine position where the
+    /// probe tripped, or `None` if the move ended without tripping
+    fn probe(&mut self, mv: &ProbeMove) -> Option<Position<NUM_AXES>>;
+}
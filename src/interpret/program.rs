@@ -0,0 +1,389 @@
+//! Programs of blocks run with `O` word control flow, following
+//! https://linuxcnc.org/docs/html/gcode/o-code.html
+//!
+//! Where every `O` word jumps is found once, when the program is created, so that
+//! mismatched statements are reported before anything moves.
+
+use crate::{
+    eval::EvalContext,
+    gcode::{
+        expression::{NamedParam, Param},
+        Block, Command, Ocode, OcodeStatement,
+    },
+    parser::{IParseResult, Input},
+    GcodeParser as _,
+};
+use alloc::vec::Vec;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ControlError {
+    /// `O` word which needs the whole program, see [`Program`]
+    NoProgram,
+    /// an end, `else`, `elseif` or loop `while` without the `O` word which opens it
+    NotOpened(u32),
+    /// `sub`, `if`, `while`, `do` or `repeat` which is not closed before the end of the
+    /// program or of the structure around it
+    NotClosed(u32),
+    /// `elseif` or `else` after the `else` of the same `if`
+    AfterElse(u32),
+    /// `break` or `continue` outside a loop with the same number
+    NotInLoop(u32),
+    /// `return` outside a subroutine with the same number
+    NotInSub(u32),
+    /// `call` of a subroutine which is not in the program
+    UnknownSub(u32),
+    /// `repeat` count which is not a whole number
+    InvalidRepeat(f32),
+}
+
+/// What an `O` word does, with the indexes of the blocks it jumps to
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Flow {
+    /// definition, skipped unless called
+    Sub {
+        end: usize,
+    },
+    EndSub,
+    Return,
+    /// `sub` is the start of the subroutine
+    Call {
+        sub: usize,
+    },
+    /// `next` is the following `elseif`, `else` or the `endif`
+    If {
+        next: usize,
+    },
+    /// reached after the branch before it ran, otherwise its condition is tested
+    ElseIf {
+        next: usize,
+        end: usize,
+    },
+    Else {
+        end: usize,
+    },
+    EndIf,
+    While {
+        end: usize,
+    },
+    EndWhile {
+        start: usize,
+    },
+    Do {
+        end: usize,
+    },
+    /// the `while` of a `do` loop, which jumps back into the body
+    DoWhile {
+        start: usize,
+    },
+    Repeat {
+        end: usize,
+    },
+    EndRepeat {
+        start: usize,
+    },
+    /// `start` of the loop, which knows its end
+    Break {
+        start: usize,
+    },
+    Continue {
+        start: usize,
+    },
+}
+
+/// Control structure opened and not yet closed
+struct Open {
+    id: u32,
+    start: usize,
+    /// `if`, `elseif` and `else` of an `if`
+    branches: Vec<usize>,
+}
+
+/// Blocks with the jumps of their `O` words, run by
+/// [`Interpreter::interpret_next`](crate::Interpreter::interpret_next)
+#[derive(Debug, Default, Clone)]
+pub struct Program {
+    pub(crate) blocks: Vec<Block>,
+    pub(crate) flows: Vec<Option<Flow>>,
+    /// index of the next block to run
+    pub(crate) next: usize,
+    /// iterations left of the running `repeat` loops of the running subroutine call,
+    /// or of the main program, by the index of their start
+    pub(crate) repeats: hashbrown::HashMap<usize, u32>,
+    /// callers of the running subroutine
+    callers: Vec<Caller>,
+}
+
+/// Where a subroutine call returns to
+#[derive(Debug, Clone)]
+struct Caller {
+    /// block after the `call`
+    next: usize,
+    /// `repeat` counts of the caller, which a recursive call must not change
+    repeats: hashbrown::HashMap<usize, u32>,
+}
+
+impl Program {
+    /// Parses a line of a program. Parameters are kept in the expressions instead of
+    /// being replaced by their values, which change while the program runs.
+    pub fn parse_block(line: &[u8]) -> IParseResult<'_, Block> {
+        Block::parse(Input::new(line, &NoFold))
+    }
+
+    pub fn new(blocks: Vec<Block>) -> Result<Self, ControlError> {
+        let mut flows: Vec<Option<Flow>> = Vec::with_capacity(blocks.len());
+        let mut open: Vec<Open> = Vec::new();
+        let mut subs = hashbrown::HashMap::new();
+        let mut calls = Vec::new();
+        for (idx, block) in blocks.iter().enumerate() {
+            let Some(ocode) = ocode(block) else {
+                flows.push(None);
+                continue;
+            };
+            let id = ocode.id;
+            let flow = match &ocode.statement {
+                OcodeStatement::Sub => {
+                    subs.insert(id, idx);
+                    open.push(Open::new(id, idx));
+                    Flow::Sub { end: idx }
+                }
+                OcodeStatement::EndSub(_) => {
+                    let start = close(&mut open, &flows, id, |flow| {
+                        matches!(flow, Flow::Sub { .. })
+                    })?
+                    .start;
+                    flows[start] = Some(Flow::Sub { end: idx });
+                    Flow::EndSub
+                }
+                OcodeStatement::Return(_) => {
+                    find(&open, &flows, id, |flow| matches!(flow, Flow::Sub { .. }))
+                        .ok_or(ControlError::NotInSub(id))?;
+                    Flow::Return
+                }
+                OcodeStatement::Call(_) => {
+                    // the subroutine may come later
+                    calls.push((idx, id));
+                    Flow::Call { sub: idx }
+                }
+                OcodeStatement::If(_) => {
+                    let mut top = Open::new(id, idx);
+                    top.branches.push(idx);
+                    open.push(top);
+                    Flow::If { next: idx }
+                }
+                OcodeStatement::ElseIf(_) | OcodeStatement::Else => {
+                    let top = open.last_mut().filter(|top| top.id == id);
+                    let top = top.ok_or(ControlError::NotOpened(id))?;
+                    let branch = top.branches.last().copied().unwrap_or(top.start);
+                    set_next(&mut flows, branch, idx, id)?;
+                    top.branches.push(idx);
+                    match ocode.statement {
+                        OcodeStatement::Else => Flow::Else { end: idx },
+                        _ => Flow::ElseIf {
+                            next: idx,
+                            end: idx,
+                        },
+                    }
+                }
+                OcodeStatement::EndIf => {
+                    let top = close(&mut open, &flows, id, |flow| {
+                        matches!(flow, Flow::If { .. })
+                    })?;
+                    for branch in top.branches {
+                        match &mut flows[branch] {
+                            // the last test jumps to the end when it fails
+                            Some(Flow::If { next }) if *next == branch => *next = idx,
+                            Some(Flow::ElseIf { next, end }) => {
+                                if *next == branch {
+                                    *next = idx;
+                                }
+                                *end = idx;
+                            }
+                            Some(Flow::Else { end }) => *end = idx,
+                            _ => {}
+                        }
+                    }
+                    Flow::EndIf
+                }
+                OcodeStatement::While(_) => {
+                    let do_loop = open.last().filter(|top| {
+                        top.id == id && matches!(flows[top.start], Some(Flow::Do { .. }))
+                    });
+                    if let Some(top) = do_loop {
+                        let start = top.start;
+                        open.pop();
+                        flows[start] = Some(Flow::Do { end: idx });
+                        Flow::DoWhile { start }
+                    } else {
+                        open.push(Open::new(id, idx));
+                        Flow::While { end: idx }
+                    }
+                }
+                OcodeStatement::EndWhile => {
+                    let start = close(&mut open, &flows, id, |flow| {
+                        matches!(flow, Flow::While { .. })
+                    })?
+                    .start;
+                    flows[start] = Some(Flow::While { end: idx });
+                    Flow::EndWhile { start }
+                }
+                OcodeStatement::Do => {
+                    open.push(Open::new(id, idx));
+                    Flow::Do { end: idx }
+                }
+                OcodeStatement::Repeat(_) => {
+                    open.push(Open::new(id, idx));
+                    Flow::Repeat { end: idx }
+                }
+                OcodeStatement::EndRepeat => {
+                    let start = close(&mut open, &flows, id, |flow| {
+                        matches!(flow, Flow::Repeat { .. })
+                    })?
+                    .start;
+                    flows[start] = Some(Flow::Repeat { end: idx });
+                    Flow::EndRepeat { start }
+                }
+                OcodeStatement::Break | OcodeStatement::Continue => {
+                    let start = find(&open, &flows, id, |flow| {
+                        matches!(
+                            flow,
+                            Flow::While { .. } | Flow::Do { .. } | Flow::Repeat { .. }
+                        )
+                    })
+                    .ok_or(ControlError::NotInLoop(id))?;
+                    match ocode.statement {
+                        OcodeStatement::Break => Flow::Break { start },
+                        _ => Flow::Continue { start },
+                    }
+                }
+            };
+            flows.push(Some(flow));
+        }
+        if let Some(top) = open.last() {
+            return Err(ControlError::NotClosed(top.id));
+        }
+        for (idx, id) in calls {
+            let sub = *subs.get(&id).ok_or(ControlError::UnknownSub(id))?;
+            flows[idx] = Some(Flow::Call { sub });
+        }
+        Ok(Self {
+            blocks,
+            flows,
+            ..Default::default()
+        })
+    }
+
+    /// Runs the subroutine starting at `sub` from the `call` at `idx`
+    pub(crate) fn call(&mut self, idx: usize, sub: usize) {
+        self.callers.push(Caller {
+            next: idx + 1,
+            repeats: core::mem::take(&mut self.repeats),
+        });
+        self.next = sub + 1;
+    }
+
+    /// Back to the block after the `call` of the running subroutine, false outside of
+    /// a subroutine call
+    pub(crate) fn return_from_call(&mut self) -> bool {
+        let Some(caller) = self.callers.pop() else {
+            return false;
+        };
+        self.next = caller.next;
+        self.repeats = caller.repeats;
+        true
+    }
+
+    /// Index of the block ending the loop which starts at `start`
+    pub(crate) fn loop_end(&self, start: usize) -> usize {
+        match self.flows[start] {
+            Some(Flow::While { end } | Flow::Do { end } | Flow::Repeat { end }) => end,
+            _ => start,
+        }
+    }
+}
+
+/// Parse context without parameter values
+#[derive(Debug)]
+struct NoFold;
+
+impl EvalContext for NoFold {
+    fn const_fold(&self) -> bool {
+        false
+    }
+
+    fn get_param(&self, _param: &Param) -> Option<f32> {
+        None
+    }
+
+    fn named_param_exists(&self, _param: &NamedParam) -> bool {
+        false
+    }
+}
+
+impl Open {
+    fn new(id: u32, start: usize) -> Self {
+        Self {
+            id,
+            start,
+            branches: Vec::new(),
+        }
+    }
+}
+
+/// The `O` word of a block, other words in the block are ignored
+pub(crate) fn ocode(block: &Block) -> Option<&Ocode> {
+    block.commands.iter().find_map(|command| match command {
+        Command::O(ocode) => Some(ocode),
+        _ => None,
+    })
+}
+
+/// Closes the innermost structure, which must have the number `id` and start with
+/// an `O` word matching `opens`
+fn close(
+    open: &mut Vec<Open>,
+    flows: &[Option<Flow>],
+    id: u32,
+    opens: impl Fn(&Flow) -> bool,
+) -> Result<Open, ControlError> {
+    let top = open.last().ok_or(ControlError::NotOpened(id))?;
+    if top.id != id {
+        return Err(match open.iter().any(|open| open.id == id) {
+            true => ControlError::NotClosed(top.id),
+            false => ControlError::NotOpened(id),
+        });
+    }
+    if !flows[top.start].as_ref().is_some_and(opens) {
+        return Err(ControlError::NotOpened(id));
+    }
+    Ok(open.pop().unwrap())
+}
+
+/// Start of the innermost open structure numbered `id` matching `kind`
+fn find(
+    open: &[Open],
+    flows: &[Option<Flow>],
+    id: u32,
+    kind: impl Fn(&Flow) -> bool,
+) -> Option<usize> {
+    open.iter()
+        .rev()
+        .find(|open| open.id == id && flows[open.start].as_ref().is_some_and(&kind))
+        .map(|open| open.start)
+}
+
+/// Points the `if` or `elseif` at `branch` to the next branch at `next`
+fn set_next(
+    flows: &mut [Option<Flow>],
+    branch: usize,
+    next: usize,
+    id: u32,
+) -> Result<(), ControlError> {
+    match &mut flows[branch] {
+        Some(Flow::If { next: to } | Flow::ElseIf { next: to, .. }) => {
+            *to = next;
+            Ok(())
+        }
+        Some(Flow::Else { .. }) => Err(ControlError::AfterElse(id)),
+        _ => Err(ControlError::NotOpened(id)),
+    }
+}
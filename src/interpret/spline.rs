@@ -0,0 +1,185 @@
+//! `G5` / `G5.1` Bézier curves and `G5.2` NURBS in the XY plane, linearized into lines
+//! which stay within a chord tolerance of the curve. All lengths are in mm.
+
+use super::{arc::PlanePoint, math::sqrt};
+use alloc::{vec, vec::Vec};
+
+/// Chord tolerance used unless the interpreter is given another one, 0.0005 inch
+pub const DEFAULT_TOLERANCE: f64 = 0.0127;
+/// Limit on the halvings of a curve, in case of a tolerance too small to reach
+const MAX_DEPTH: u32 = 16;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum SplineError {
+    /// splines outside the XY plane are not supported
+    UnsupportedPlane,
+    /// `I` and `J` of a `G5.1` are both zero
+    ZeroOffsets,
+    /// `P` weight of a NURBS control point which is not positive
+    InvalidWeight(f32),
+    /// `L` order of a NURBS which is not an integer of at least 2
+    InvalidOrder(f32),
+    /// `G5.3` with fewer control points than the order of the NURBS
+    TooFewPoints,
+    /// a motion other than control points between `G5.2` and `G5.3`
+    NurbsNotEnded,
+    /// `G5.3` or control point words without a `G5.2`
+    NoNurbs,
+}
+
+/// End points of the lines approximating the Bézier curve with the given control
+/// points, from the first to the last
+pub fn bezier(control: &[PlanePoint], tolerance: f64) -> Vec<PlanePoint> {
+    let mut lines = Vec::new();
+    bezier_lines(control.to_vec(), tolerance, 0, &mut lines);
+    lines
+}
+
+fn bezier_lines(control: Vec<PlanePoint>, tolerance: f64, depth: u32, lines: &mut Vec<PlanePoint>) {
+    let (start, end) = (control[0], control[control.len() - 1]);
+    // the curve stays within the hull of its control points
+    let flat = control[1..control.len() - 1]
+        .iter()
+        .all(|point| distance_to_chord(*point, start, end) <= tolerance);
+    if flat || depth == MAX_DEPTH {
+        lines.push(end);
+        return;
+    }
+    let (first, second) = split(&control);
+    bezier_lines(first, tolerance, depth + 1, lines);
+    bezier_lines(second, tolerance, depth + 1, lines);
+}
+
+/// de Casteljau's split of a Bézier curve into its two halves
+fn split(control: &[PlanePoint]) -> (Vec<PlanePoint>, Vec<PlanePoint>) {
+    let mut first = vec![control[0]];
+    let mut second = vec![control[control.len() - 1]];
+    let mut points = control.to_vec();
+    while points.len() > 1 {
+        points = points
+            .windows(2)
+            .map(|pair| midpoint(pair[0], pair[1]))
+            .collect();
+        first.push(points[0]);
+        second.push(points[points.len() - 1]);
+    }
+    second.reverse();
+    (first, second)
+}
+
+/// A NURBS curve with a clamped, uniform knot vector, so that it starts at its first
+/// control point and ends at its last
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Nurbs {
+    order: usize,
+    /// control points with their weights
+    points: Vec<(PlanePoint, f64)>,
+}
+
+impl Nurbs {
+    pub fn new(order: usize) -> Self {
+        Self {
+            order,
+            points: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, point: PlanePoint, weight: f64) {
+        self.points.push((point, weight));
+    }
+
+    pub fn point_count(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// End points of the lines approximating the curve, from its start to its end.
+    /// Needs at least `order` control points.
+    pub fn linearize(&self, tolerance: f64) -> Vec<PlanePoint> {
+        let knots = self.knots();
+        let mut lines = Vec::new();
+        for span in knots.windows(2) {
+            if span[1] > span[0] {
+                self.lines(span[0], span[1], tolerance, 0, &mut lines);
+            }
+        }
+        lines
+    }
+
+    fn lines(&self, from: f64, to: f64, tolerance: f64, depth: u32, lines: &mut Vec<PlanePoint>) {
+        let (start, end) = (self.point(from), self.point(to));
+        let flat = [0.25, 0.5, 0.75].into_iter().all(|fraction| {
+            let point = self.point(from + (to - from) * fraction);
+            distance_to_chord(point, start, end) <= tolerance
+        });
+        if flat || depth == MAX_DEPTH {
+            lines.push(end);
+            return;
+        }
+        let middle = (from + to) / 2.0;
+        self.lines(from, middle, tolerance, depth + 1, lines);
+        self.lines(middle, to, tolerance, depth + 1, lines);
+    }
+
+    /// Clamped uniform knot vector from 0 to 1
+    fn knots(&self) -> Vec<f64> {
+        let degree = self.order - 1;
+        let spans = self.points.len() - degree;
+        let mut knots = vec![0.0; degree];
+        knots.extend((0..=spans).map(|idx| idx as f64 / spans as f64));
+        knots.extend(vec![1.0; degree]);
+        knots
+    }
+
+    /// Point of the curve at `t` from 0 to 1, by de Boor's algorithm on the weighted
+    /// control points
+    fn point(&self, t: f64) -> PlanePoint {
+        let degree = self.order - 1;
+        let knots = self.knots();
+        let last = self.points.len() - 1;
+        let span = (degree..=last)
+            .rev()
+            .find(|&span| knots[span] <= t)
+            .unwrap_or(degree);
+        let mut homogeneous: Vec<[f64; 3]> = (0..=degree)
+            .map(|idx| {
+                let (point, weight) = self.points[span - degree + idx];
+                [point[0] * weight, point[1] * weight, weight]
+            })
+            .collect();
+        for level in 1..=degree {
+            for idx in (level..=degree).rev() {
+                let knot = span - degree + idx;
+                let alpha = (t - knots[knot]) / (knots[knot + degree + 1 - level] - knots[knot]);
+                let previous = homogeneous[idx - 1];
+                for (coord, previous) in homogeneous[idx].iter_mut().zip(previous) {
+                    *coord = (1.0 - alpha) * previous + alpha * *coord;
+                }
+            }
+        }
+        let [x, y, weight] = homogeneous[degree];
+        [x / weight, y / weight]
+    }
+}
+
+fn midpoint(a: PlanePoint, b: PlanePoint) -> PlanePoint {
+    [(a[0] + b[0]) / 2.0, (a[1] + b[1]) / 2.0]
+}
+
+/// Distance of `point` from the line segment between `start` and `end`
+fn distance_to_chord(point: PlanePoint, start: PlanePoint, end: PlanePoint) -> f64 {
+    let chord = [end[0] - start[0], end[1] - start[1]];
+    let offset = [point[0] - start[0], point[1] - start[1]];
+    let length_squared = chord[0] * chord[0] + chord[1] * chord[1];
+    let along = if length_squared == 0.0 {
+        0.0
+    } else {
+        ((offset[0] * chord[0] + offset[1] * chord[1]) / length_squared).clamp(0.0, 1.0)
+    };
+    let dx = offset[0] - along * chord[0];
+    let dy = offset[1] - along * chord[1];
+    sqrt(dx * dx + dy * dy)
+}
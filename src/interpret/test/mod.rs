@@ -1,12 +1,30 @@
-use super::interpreter::InterpretValue;
+use super::interpreter::{InterpretError, InterpretValue};
 use crate::{
-    gcode::Command, interpret::model_state::ModelStateUnit, GcodeParser as _, Interpreter,
+    gcode::{Block, Command},
+    interpret::{
+        model_state::{Coolant, MmSec, ModelStateUnit, Plane, Position, SpindleDirection},
+        motion::{MotionEvent, Move},
+    },
+    DwellUnit, GcodeParser as _, Interpreter, NUM_AXES,
 };
-use alloc::boxed::Box;
-use core::error::Error;
+use alloc::{boxed::Box, vec::Vec};
+use core::{error::Error, time::Duration};
 
 extern crate std;
 
+mod test_arc;
+mod test_canned_cycle;
+mod test_control_flow;
+mod test_cutter_comp;
+mod test_feed;
+mod test_motion;
+mod test_probe;
+mod test_spindle;
+mod test_spline;
+mod test_threading;
+mod test_tool;
+mod test_workspace;
+
 fn try_parse_interpret(
     interpreter: &mut Interpreter,
     input: &[u8],
@@ -32,6 +50,72 @@ fn try_interpret(
         .into()
     })
 }
+fn try_interpret_block(
+    interpreter: &mut Interpreter,
+    input: &[u8],
+) -> Result<Vec<InterpretValue>, Box<dyn Error>> {
+    use crate::parser::Input;
+    let block = {
+        let input = Input::new(input, interpreter);
+        Block::parse(input)
+            .map_err(|e| std::format!("error parsing {}: {:?}", input.as_utf8().unwrap(), e))?
+            .1
+    };
+    interpreter.interpret_block(block).map_err(|e| {
+        std::format!(
+            "error interpreting {}: {:?}",
+            std::str::from_utf8(input).unwrap(),
+            e
+        )
+        .into()
+    })
+}
+
+/// The error of a command which fails to interpret
+#[track_caller]
+fn interpret_error(interpreter: &mut Interpreter, input: &[u8]) -> InterpretError {
+    let command = try_parse_interpret(interpreter, input).unwrap();
+    interpreter.interpret(command).unwrap_err()
+}
+
+#[track_caller]
+fn assert_position(actual: &Position<NUM_AXES>, expected: [f64; NUM_AXES]) {
+    for (idx, expected) in expected.into_iter().enumerate() {
+        assert!(
+            (actual[idx] - expected).abs() < 1e-4,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+}
+
+/// The move produced by a command which results in exactly one move
+#[track_caller]
+fn single_move(value: InterpretValue) -> Move {
+    match value {
+        InterpretValue::Motion(events) => match &events[..] {
+            [MotionEvent::Move(mv)] => mv.clone(),
+            other => panic!("expected a single move, got {:?}", other),
+        },
+        other => panic!("expected motion, got {:?}", other),
+    }
+}
+
+/// Every move of a command which results in moves only
+#[track_caller]
+fn moves(value: InterpretValue) -> Vec<Move> {
+    match value {
+        InterpretValue::Motion(events) => events
+            .into_iter()
+            .map(|event| match event {
+                MotionEvent::Move(mv) => mv,
+                other => panic!("expected a move, got {:?}", other),
+            })
+            .collect(),
+        other => panic!("expected motion, got {:?}", other),
+    }
+}
 
 #[test]
 fn test_interpret_context() -> Result<(), Box<dyn Error>> {
@@ -55,6 +139,39 @@ fn test_interpret_context() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_spindle_direction_and_coolant() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    let state = interpreter.get_model_state();
+    assert_eq!(state.spindle_direction, SpindleDirection::Stopped);
+    assert_eq!(state.coolant, Coolant::default());
+
+    try_interpret(&mut interpreter, b"M4")?;
+    try_interpret(&mut interpreter, b"M7")?;
+    let state = interpreter.get_model_state();
+    assert_eq!(state.spindle_direction, SpindleDirection::CounterClockwise);
+    assert_eq!(
+        state.coolant,
+        Coolant {
+            mist: true,
+            flood: false
+        }
+    );
+
+    try_interpret(&mut interpreter, b"M3")?;
+    try_interpret(&mut interpreter, b"M8")?;
+    let state = interpreter.get_model_state();
+    assert_eq!(state.spindle_direction, SpindleDirection::Clockwise);
+    assert!(state.coolant.mist && state.coolant.flood);
+
+    try_interpret(&mut interpreter, b"M5")?;
+    try_interpret(&mut interpreter, b"M9")?;
+    let state = interpreter.get_model_state();
+    assert_eq!(state.spindle_direction, SpindleDirection::Stopped);
+    assert_eq!(state.coolant, Coolant::default());
+    Ok(())
+}
+
 #[test]
 fn test_interpret_assign() -> Result<(), Box<dyn Error>> {
     let mut interpreter = Interpreter::default();
@@ -88,3 +205,136 @@ fn test_interpret_assign() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_interpret_assign_in_block() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret(&mut interpreter, b"#1 = 1")?;
+
+    // the words of a block read the parameters from before its assignments
+    let (_, block) = crate::Program::parse_block(b"#1 = 5 #2 = #1 G0 X#1").unwrap();
+    let values = interpreter.interpret_block(block).unwrap();
+    let mv = single_move(values.into_iter().last().unwrap());
+    assert_position(&mv.to, [1.0, 0.0, 0.0]);
+    assert_eq!(interpreter.get_numbered_param(1), Some(5.0));
+    assert_eq!(interpreter.get_numbered_param(2), Some(1.0));
+
+    // the next block reads the new value
+    let (_, block) = crate::Program::parse_block(b"G0 X#1").unwrap();
+    let mv = single_move(interpreter.interpret_block(block).unwrap().remove(0));
+    assert_position(&mv.to, [5.0, 0.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn test_interpret_block() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+
+    let values = try_interpret_block(&mut interpreter, b"N10 #1 = 2 #2 = 3 (set params)")?;
+    assert_eq!(
+        values,
+        [
+            InterpretValue::Other,
+            InterpretValue::EvalExpr(2.0),
+            InterpretValue::EvalExpr(3.0)
+        ]
+    );
+    assert_eq!(interpreter.get_numbered_param(2), Some(3.0));
+
+    try_interpret_block(&mut interpreter, b"%")?;
+    try_interpret_block(&mut interpreter, b"G20")?;
+    assert_eq!(
+        interpreter.get_model_state().selected_unit,
+        ModelStateUnit::In
+    );
+    Ok(())
+}
+
+#[test]
+fn test_interpret_block_delete() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+
+    // switch off: block delete lines are run
+    try_interpret_block(&mut interpreter, b"/ #1 = 1")?;
+    assert_eq!(interpreter.get_numbered_param(1), Some(1.0));
+
+    // switch on: block delete lines are skipped
+    interpreter.set_block_delete(true);
+    let values = try_interpret_block(&mut interpreter, b"/ #1 = 2")?;
+    assert!(values.is_empty());
+    assert_eq!(interpreter.get_numbered_param(1), Some(1.0));
+
+    // lines without `/` still run
+    try_interpret_block(&mut interpreter, b"#1 = 3")?;
+    assert_eq!(interpreter.get_numbered_param(1), Some(3.0));
+    Ok(())
+}
+
+#[test]
+fn test_interpret_feedrate() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    assert_eq!(interpreter.get_model_state().feedrate, MmSec(0.0));
+
+    // mm per minute
+    try_interpret(&mut interpreter, b"F600")?;
+    assert_eq!(interpreter.get_model_state().feedrate, MmSec(10.0));
+
+    // expressions and parameters
+    try_interpret(&mut interpreter, b"#1 = 200")?;
+    try_interpret(&mut interpreter, b"F[#1 * 6]")?;
+    assert_eq!(interpreter.get_model_state().programmed_feedrate, 1200.0);
+    assert_eq!(interpreter.get_model_state().feedrate, MmSec(20.0));
+
+    // inches per minute, F is read in the units selected on the same line
+    try_interpret_block(&mut interpreter, b"F60 G20")?;
+    assert_eq!(interpreter.get_model_state().feedrate, MmSec(25.4));
+
+    // switching units keeps the programmed value
+    try_interpret(&mut interpreter, b"G21")?;
+    assert_eq!(interpreter.get_model_state().feedrate, MmSec(1.0));
+
+    assert!(try_interpret(&mut interpreter, b"F-1").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_interpret_dwell() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+
+    // seconds by default
+    let value = try_interpret(&mut interpreter, b"G4 P1.5")?;
+    assert_eq!(
+        value,
+        InterpretValue::Motion(std::vec![MotionEvent::Dwell(Duration::from_millis(1500))])
+    );
+
+    // milliseconds, as Marlin reads it
+    interpreter.set_dwell_unit(DwellUnit::Milliseconds);
+    let value = try_interpret(&mut interpreter, b"G4 P250")?;
+    assert_eq!(
+        value,
+        InterpretValue::Motion(std::vec![MotionEvent::Dwell(Duration::from_millis(250))])
+    );
+
+    assert!(try_interpret(&mut interpreter, b"G4 P-1").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_interpret_plane() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    assert_eq!(interpreter.get_model_state().plane, Plane::XY);
+
+    for (input, plane) in [
+        (&b"G18"[..], Plane::XZ),
+        (b"G19", Plane::YZ),
+        (b"G17.1", Plane::UV),
+        (b"G18.1", Plane::UW),
+        (b"G19.1", Plane::VW),
+        (b"G17", Plane::XY),
+    ] {
+        try_interpret(&mut interpreter, input)?;
+        assert_eq!(interpreter.get_model_state().plane, plane);
+    }
+    Ok(())
+}
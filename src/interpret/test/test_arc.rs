@@ -0,0 +1,212 @@
+use super::{assert_position, single_move, try_interpret, try_interpret_block};
+use crate::{
+    interpret::{
+        arc::ArcError,
+        interpreter::InterpretError,
+        motion::{ArcMove, MoveKind},
+    },
+    Interpreter,
+};
+use alloc::boxed::Box;
+use core::error::Error;
+
+extern crate std;
+
+#[track_caller]
+fn arc_move(interpreter: &mut Interpreter, input: &[u8]) -> ArcMove {
+    let mv = single_move(try_interpret(interpreter, input).unwrap());
+    let MoveKind::Arc(arc) = mv.kind else {
+        panic!("expected an arc, got {:?}", mv.kind);
+    };
+    arc
+}
+
+#[track_caller]
+fn arc_error(interpreter: &mut Interpreter, input: &[u8]) -> ArcError {
+    use crate::{gcode::Command, parser::Input, GcodeParser as _};
+    let command = Command::parse(Input::new(input, interpreter)).unwrap().1;
+    match interpreter.interpret(command) {
+        Err(InterpretError::Arc(err)) => err,
+        other => panic!("expected an arc error, got {:?}", other),
+    }
+}
+
+fn interpreter_at_origin() -> Interpreter {
+    let mut interpreter = Interpreter::default();
+    try_interpret(&mut interpreter, b"F600").unwrap();
+    interpreter
+}
+
+#[test]
+fn test_arc_ijk() {
+    let mut interpreter = interpreter_at_origin();
+
+    // clockwise half circle around (5, 0)
+    let arc = arc_move(&mut interpreter, b"G2 X10 Y0 I5 J0");
+    assert_position(&arc.center, [5.0, 0.0, 0.0]);
+    assert_eq!(arc.turns, -1);
+    assert_position(
+        &interpreter.get_model_state().abs_position,
+        [10.0, 0.0, 0.0],
+    );
+
+    // counter-clockwise quarter circle back up around (10, 5)
+    let arc = arc_move(&mut interpreter, b"G3 X15 Y5 J5");
+    assert_position(&arc.center, [10.0, 5.0, 0.0]);
+    assert_eq!(arc.turns, 1);
+}
+
+#[test]
+fn test_arc_full_circle_helical() {
+    let mut interpreter = interpreter_at_origin();
+    let mv = single_move(try_interpret(&mut interpreter, b"G3 Z-1 I5 P2").unwrap());
+    assert_position(&mv.to, [0.0, 0.0, -1.0]);
+    let MoveKind::Arc(arc) = mv.kind else {
+        panic!("expected an arc, got {:?}", mv.kind);
+    };
+    assert_position(&arc.center, [5.0, 0.0, 0.0]);
+    assert_eq!(arc.turns, 2);
+}
+
+#[test]
+fn test_arc_radius() {
+    let mut interpreter = interpreter_at_origin();
+
+    // quarter circles to (10, 10), the short way round
+    let arc = arc_move(&mut interpreter, b"G2 X10 Y10 R10");
+    assert_position(&arc.center, [10.0, 0.0, 0.0]);
+
+    try_interpret(&mut interpreter, b"G3 X0 Y0 R10").unwrap();
+    let arc = arc_move(&mut interpreter, b"G3 X10 Y10 R10");
+    assert_position(&arc.center, [0.0, 10.0, 0.0]);
+
+    // negative radius takes the long way round
+    try_interpret(&mut interpreter, b"G3 X0 Y0 R10").unwrap();
+    let arc = arc_move(&mut interpreter, b"G2 X10 Y10 R-10");
+    assert_position(&arc.center, [0.0, 10.0, 0.0]);
+
+    // semicircle
+    try_interpret(&mut interpreter, b"G3 X0 Y0 R10").unwrap();
+    let arc = arc_move(&mut interpreter, b"G2 X10 Y0 R5");
+    assert_position(&arc.center, [5.0, 0.0, 0.0]);
+}
+
+#[test]
+fn test_arc_radius_oblique() {
+    let mut interpreter = interpreter_at_origin();
+    try_interpret_block(&mut interpreter, b"G0 X1 Y2").unwrap();
+
+    // chord of 5 at an angle, the center is 6 away from its middle
+    let arc = arc_move(&mut interpreter, b"G3 X4 Y6 R6.5");
+    assert_position(&arc.center, [-2.3, 7.6, 0.0]);
+    let arc = arc_move(&mut interpreter, b"G3 X1 Y2 R6.5");
+    assert_position(&arc.center, [7.3, 0.4, 0.0]);
+
+    // start and end radius are compared exactly
+    let mv = single_move(try_interpret(&mut interpreter, b"G2 X4 Y6 I1.5 J2").unwrap());
+    assert_position(&mv.to, [4.0, 6.0, 0.0]);
+    let MoveKind::Arc(arc) = mv.kind else {
+        panic!("expected an arc, got {:?}", mv.kind);
+    };
+    assert_position(&arc.center, [2.5, 4.0, 0.0]);
+}
+
+#[test]
+fn test_arc_inches() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_at_origin();
+    try_interpret_block(&mut interpreter, b"G20")?;
+    let arc = arc_move(&mut interpreter, b"G2 X1 I0.5");
+    assert_position(&arc.center, [12.7, 0.0, 0.0]);
+    assert_position(
+        &interpreter.get_model_state().abs_position,
+        [25.4, 0.0, 0.0],
+    );
+    Ok(())
+}
+
+#[test]
+fn test_arc_radius_tolerance() {
+    let mut interpreter = interpreter_at_origin();
+
+    // end point 0.02mm off the circle is within tolerance
+    arc_move(&mut interpreter, b"G2 X10.02 I5");
+
+    // 0.1mm off is not
+    try_interpret(&mut interpreter, b"G3 X0 I-5.01").unwrap();
+    match arc_error(&mut interpreter, b"G2 X10.1 I5") {
+        ArcError::RadiusMismatch { start, end } => {
+            assert!((start - 5.0).abs() < 1e-4, "{}", start);
+            assert!((end - 5.1).abs() < 1e-4, "{}", end);
+        }
+        other => panic!("expected a radius mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_arc_errors() {
+    let mut interpreter = interpreter_at_origin();
+    assert_eq!(
+        arc_error(&mut interpreter, b"G2 X10 R5 I5"),
+        ArcError::MixedRadiusOffsets
+    );
+    assert_eq!(
+        arc_error(&mut interpreter, b"G2 X10"),
+        ArcError::MissingRadiusOffsets
+    );
+    assert_eq!(
+        arc_error(&mut interpreter, b"G2 X10 I5 K1"),
+        ArcError::OffsetOutOfPlane
+    );
+    assert_eq!(arc_error(&mut interpreter, b"G2 I0"), ArcError::ZeroRadius);
+    assert_eq!(
+        arc_error(&mut interpreter, b"G2 R5"),
+        ArcError::EndSameAsStart
+    );
+    assert_eq!(
+        arc_error(&mut interpreter, b"G2 X20 R5"),
+        ArcError::RadiusTooSmall
+    );
+    assert_eq!(
+        arc_error(&mut interpreter, b"G2 X10 I5 P0"),
+        ArcError::InvalidTurns(0.0)
+    );
+    assert_eq!(
+        arc_error(&mut interpreter, b"G2 X10 I5 P1.5"),
+        ArcError::InvalidTurns(1.5)
+    );
+}
+
+#[test]
+fn test_arc_plane() {
+    let mut interpreter = interpreter_at_origin();
+
+    // XZ plane, Y moves helically
+    try_interpret(&mut interpreter, b"G18").unwrap();
+    let arc = arc_move(&mut interpreter, b"G2 X10 Y2 I5 K0");
+    assert_position(&arc.center, [5.0, 0.0, 0.0]);
+    assert_position(
+        &interpreter.get_model_state().abs_position,
+        [10.0, 2.0, 0.0],
+    );
+    assert_eq!(
+        arc_error(&mut interpreter, b"G2 X0 I-5 J0"),
+        ArcError::OffsetOutOfPlane
+    );
+
+    // YZ plane
+    try_interpret(&mut interpreter, b"G19").unwrap();
+    let arc = arc_move(&mut interpreter, b"G3 Y2 Z10 K5");
+    assert_position(&arc.center, [10.0, 2.0, 5.0]);
+
+    try_interpret(&mut interpreter, b"G17.1").unwrap();
+    assert_eq!(
+        arc_error(&mut interpreter, b"G2 X0 I5"),
+        ArcError::UnsupportedPlane
+    );
+}
+
+#[test]
+fn test_arc_zero_feedrate() {
+    let mut interpreter = Interpreter::default();
+    assert!(try_interpret(&mut interpreter, b"G2 X10 I5").is_err());
+}
@@ -0,0 +1,299 @@
+use super::{interpret_error, try_interpret, try_interpret_block};
+use crate::{
+    interpret::{
+        canned_cycle::{CycleError, CycleKind},
+        interpreter::{InterpretError, InterpretValue},
+        model_state::{MotionMode, RetractMode},
+        motion::{MotionEvent, MoveKind},
+    },
+    Interpreter,
+};
+use alloc::boxed::Box;
+use core::{error::Error, time::Duration};
+
+extern crate std;
+
+#[derive(Debug)]
+enum Step {
+    Rapid([f64; 3]),
+    Feed([f64; 3]),
+    Dwell(f64),
+}
+use Step::*;
+
+#[track_caller]
+fn assert_steps(value: InterpretValue, expected: &[Step]) {
+    let InterpretValue::Motion(events) = value else {
+        panic!("expected motion, got {:?}", value);
+    };
+    assert_eq!(
+        events.len(),
+        expected.len(),
+        "{:?} != {:?}",
+        events,
+        expected
+    );
+    for (event, step) in events.iter().zip(expected) {
+        let matches = match (event, step) {
+            (MotionEvent::Move(mv), Rapid(to)) if mv.kind == MoveKind::Rapid => {
+                (0..3).all(|idx| (mv.to[idx] - to[idx]).abs() < 1e-4)
+            }
+            (MotionEvent::Move(mv), Feed(to)) if mv.kind == MoveKind::Linear => {
+                (0..3).all(|idx| (mv.to[idx] - to[idx]).abs() < 1e-4)
+            }
+            (MotionEvent::Dwell(duration), Dwell(secs)) => {
+                *duration == Duration::from_secs_f64(*secs)
+            }
+            _ => false,
+        };
+        assert!(matches, "{:?} != {:?}", events, expected);
+    }
+}
+
+/// At X0 Y0 Z`z`, with a feedrate
+fn interpreter_at(z: f64) -> Interpreter {
+    let mut interpreter = Interpreter::default();
+    try_interpret(&mut interpreter, b"F600").unwrap();
+    let input = std::format!("G0 X0 Y0 Z{z}");
+    try_interpret(&mut interpreter, input.as_bytes()).unwrap();
+    interpreter
+}
+
+#[test]
+fn test_cycle_drill_modal() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_at(10.0);
+
+    // G98: back to the start level
+    let value = try_interpret(&mut interpreter, b"G81 X5 Y5 Z-2 R1")?;
+    assert_steps(
+        value,
+        &[
+            Rapid([5.0, 5.0, 10.0]),
+            Rapid([5.0, 5.0, 1.0]),
+            Feed([5.0, 5.0, -2.0]),
+            Rapid([5.0, 5.0, 10.0]),
+        ],
+    );
+    assert_eq!(
+        interpreter.get_model_state().motion_mode,
+        MotionMode::CannedCycle(CycleKind::G81)
+    );
+
+    // axis words alone repeat the cycle
+    let values = try_interpret_block(&mut interpreter, b"X10")?;
+    assert_steps(
+        values.into_iter().next().unwrap(),
+        &[
+            Rapid([10.0, 5.0, 10.0]),
+            Rapid([10.0, 5.0, 1.0]),
+            Feed([10.0, 5.0, -2.0]),
+            Rapid([10.0, 5.0, 10.0]),
+        ],
+    );
+
+    // G99: back to R, the next hole starts there
+    try_interpret(&mut interpreter, b"G99")?;
+    assert_eq!(interpreter.get_model_state().retract_mode, RetractMode::R);
+    let value = try_interpret(&mut interpreter, b"Y10")?;
+    assert_steps(
+        value,
+        &[
+            Rapid([10.0, 10.0, 10.0]),
+            Rapid([10.0, 10.0, 1.0]),
+            Feed([10.0, 10.0, -2.0]),
+            Rapid([10.0, 10.0, 1.0]),
+        ],
+    );
+    let value = try_interpret(&mut interpreter, b"X0 Z-3")?;
+    assert_steps(
+        value,
+        &[
+            Rapid([0.0, 10.0, 1.0]),
+            Feed([0.0, 10.0, -3.0]),
+            Rapid([0.0, 10.0, 1.0]),
+        ],
+    );
+
+    try_interpret(&mut interpreter, b"G80")?;
+    assert_eq!(
+        interpret_error(&mut interpreter, b"X1"),
+        InterpretError::NoMotionMode
+    );
+    Ok(())
+}
+
+#[test]
+fn test_cycle_below_r() -> Result<(), Box<dyn Error>> {
+    // starting below R, Z goes up to R first
+    let mut interpreter = interpreter_at(0.0);
+    let value = try_interpret(&mut interpreter, b"G81 X2 Z-2 R3")?;
+    assert_steps(
+        value,
+        &[
+            Rapid([0.0, 0.0, 3.0]),
+            Rapid([2.0, 0.0, 3.0]),
+            Feed([2.0, 0.0, -2.0]),
+            Rapid([2.0, 0.0, 3.0]),
+        ],
+    );
+    Ok(())
+}
+
+#[test]
+fn test_cycle_peck() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_at(5.0);
+    try_interpret(&mut interpreter, b"G99")?;
+
+    // G83 goes back up to R after every peck
+    let value = try_interpret(&mut interpreter, b"G83 Z-5 R1 Q2")?;
+    assert_steps(
+        value,
+        &[
+            Rapid([0.0, 0.0, 1.0]),
+            Feed([0.0, 0.0, -1.0]),
+            Rapid([0.0, 0.0, 1.0]),
+            Rapid([0.0, 0.0, -0.746]),
+            Feed([0.0, 0.0, -3.0]),
+            Rapid([0.0, 0.0, 1.0]),
+            Rapid([0.0, 0.0, -2.746]),
+            Feed([0.0, 0.0, -5.0]),
+            Rapid([0.0, 0.0, 1.0]),
+        ],
+    );
+
+    // G73 only backs off to break the chip, Z, R and Q carry over
+    let value = try_interpret(&mut interpreter, b"G73 X1")?;
+    assert_steps(
+        value,
+        &[
+            Rapid([1.0, 0.0, 1.0]),
+            Feed([1.0, 0.0, -1.0]),
+            Rapid([1.0, 0.0, -0.746]),
+            Feed([1.0, 0.0, -3.0]),
+            Rapid([1.0, 0.0, -2.746]),
+            Feed([1.0, 0.0, -5.0]),
+            Rapid([1.0, 0.0, 1.0]),
+        ],
+    );
+    Ok(())
+}
+
+#[test]
+fn test_cycle_dwell_and_boring() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_at(5.0);
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G82 Z-2 R1"),
+        InterpretError::MissingWord('P')
+    );
+    let value = try_interpret(&mut interpreter, b"G82 P0.5")?;
+    assert_steps(
+        value,
+        &[
+            Rapid([0.0, 0.0, 1.0]),
+            Feed([0.0, 0.0, -2.0]),
+            Dwell(0.5),
+            Rapid([0.0, 0.0, 5.0]),
+        ],
+    );
+
+    // feed back out to R, then rapid to the start level
+    let value = try_interpret(&mut interpreter, b"G85 X1")?;
+    assert_steps(
+        value,
+        &[
+            Rapid([1.0, 0.0, 5.0]),
+            Rapid([1.0, 0.0, 1.0]),
+            Feed([1.0, 0.0, -2.0]),
+            Feed([1.0, 0.0, 1.0]),
+            Rapid([1.0, 0.0, 5.0]),
+        ],
+    );
+    let value = try_interpret(&mut interpreter, b"G89 X2")?;
+    assert_steps(
+        value,
+        &[
+            Rapid([2.0, 0.0, 5.0]),
+            Rapid([2.0, 0.0, 1.0]),
+            Feed([2.0, 0.0, -2.0]),
+            Dwell(0.5),
+            Feed([2.0, 0.0, 1.0]),
+            Rapid([2.0, 0.0, 5.0]),
+        ],
+    );
+    Ok(())
+}
+
+#[test]
+fn test_cycle_back_boring() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_at(10.0);
+    let value = try_interpret(&mut interpreter, b"G87 Z-10 R1 I2 K-5")?;
+    assert_steps(
+        value,
+        &[
+            Rapid([0.0, 0.0, 1.0]),
+            Rapid([2.0, 0.0, 1.0]),
+            Rapid([2.0, 0.0, -10.0]),
+            Rapid([0.0, 0.0, -10.0]),
+            Feed([0.0, 0.0, -5.0]),
+            Feed([0.0, 0.0, -10.0]),
+            Rapid([2.0, 0.0, -10.0]),
+            Rapid([2.0, 0.0, 10.0]),
+            Rapid([0.0, 0.0, 10.0]),
+        ],
+    );
+    Ok(())
+}
+
+#[test]
+fn test_cycle_incremental_repeats() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_at(5.0);
+    try_interpret(&mut interpreter, b"G91")?;
+
+    // R is below the start, the bottom below R, every hole 10 further along X
+    let value = try_interpret(&mut interpreter, b"G81 X10 Z-3 R-4 L2")?;
+    assert_steps(
+        value,
+        &[
+            Rapid([10.0, 0.0, 5.0]),
+            Rapid([10.0, 0.0, 1.0]),
+            Feed([10.0, 0.0, -2.0]),
+            Rapid([10.0, 0.0, 5.0]),
+            Rapid([20.0, 0.0, 5.0]),
+            Rapid([20.0, 0.0, 1.0]),
+            Feed([20.0, 0.0, -2.0]),
+            Rapid([20.0, 0.0, 5.0]),
+        ],
+    );
+    Ok(())
+}
+
+#[test]
+fn test_cycle_errors() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_at(5.0);
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G81 X1"),
+        InterpretError::MissingWord('Z')
+    );
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G81 Z1 R-1"),
+        InterpretError::CannedCycle(CycleError::BottomAboveR)
+    );
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G83 Z-1 R1"),
+        InterpretError::MissingWord('Q')
+    );
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G83 Z-1 R1 Q0"),
+        InterpretError::CannedCycle(CycleError::InvalidPeck(0.0))
+    );
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G81 Z-1 R1 L0"),
+        InterpretError::CannedCycle(CycleError::InvalidRepeats(0.0))
+    );
+    try_interpret(&mut interpreter, b"G17.1")?;
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G81 Z-1 R1"),
+        InterpretError::CannedCycle(CycleError::UnsupportedPlane)
+    );
+    Ok(())
+}
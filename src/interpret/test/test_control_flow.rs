@@ -0,0 +1,314 @@
+use crate::{
+    gcode::Block,
+    interpret::{
+        interpreter::{InterpretError, InterpretValue},
+        motion::MotionEvent,
+        program::{ControlError, Program},
+    },
+    Interpreter,
+};
+use alloc::{boxed::Box, vec::Vec};
+use core::error::Error;
+
+extern crate std;
+
+/// Blocks of the lines of `source`
+fn blocks(source: &str) -> Vec<Block> {
+    source
+        .lines()
+        .map(|line| Program::parse_block(line.trim().as_bytes()).unwrap().1)
+        .collect()
+}
+
+/// X of every move of `source`, which starts with `#1` set to `first`
+fn run(first: f32, source: &str) -> Result<Vec<f64>, Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    let source = std::format!("#1 = {first}\n{source}");
+    let blocks = blocks(&source);
+    let mut program = Program::new(blocks).map_err(|e| std::format!("{:?}", e))?;
+    let values = interpreter
+        .interpret_program(&mut program)
+        .map_err(|e| std::format!("{:?}", e))?;
+    Ok(values
+        .into_iter()
+        .flat_map(|value| match value {
+            InterpretValue::Motion(events) => events,
+            _ => Vec::new(),
+        })
+        .filter_map(|event| match event {
+            MotionEvent::Move(mv) => Some(mv.to[0]),
+            _ => None,
+        })
+        .collect())
+}
+
+#[test]
+fn test_parse_block_keeps_params() {
+    use crate::gcode::{expression::*, Axes, Axis, Gcode};
+    // not replaced by a value, which a loop may change
+    let x = Expression::param(Param::numbered(1));
+    assert_eq!(
+        blocks("G0 X#1"),
+        [Block::new([
+            Gcode::G0(Some(Axes::new().set(Axis::X, x))).into()
+        ])]
+    );
+}
+
+#[test]
+fn test_while() -> Result<(), Box<dyn Error>> {
+    let source = "O1 while [#1 LT 3]
+        #1 = [#1 + 1]
+        G0 X#1
+        O1 endwhile
+        G0 X10";
+    assert_eq!(run(0.0, source)?, [1.0, 2.0, 3.0, 10.0]);
+    // the condition is tested before the first iteration
+    assert_eq!(run(5.0, source)?, [10.0]);
+    Ok(())
+}
+
+#[test]
+fn test_do_while() -> Result<(), Box<dyn Error>> {
+    let source = "O1 do
+        #1 = [#1 + 1]
+        G0 X#1
+        O1 while [#1 LT 3]";
+    assert_eq!(run(0.0, source)?, [1.0, 2.0, 3.0]);
+    // the body runs at least once
+    assert_eq!(run(5.0, source)?, [6.0]);
+    Ok(())
+}
+
+#[test]
+fn test_repeat() -> Result<(), Box<dyn Error>> {
+    let source = "#2 = 0
+        O1 repeat [#1]
+        #2 = [#2 + 1]
+        G0 X#2
+        O1 endrepeat";
+    assert_eq!(run(3.0, source)?, [1.0, 2.0, 3.0]);
+    assert_eq!(run(0.0, source)?, []);
+
+    // nested, the inner count starts over on every outer iteration
+    let source = "#2 = 0
+        O1 repeat [2]
+        O2 repeat [#1]
+        #2 = [#2 + 1]
+        G0 X#2
+        O2 endrepeat
+        O1 endrepeat";
+    assert_eq!(run(2.0, source)?, [1.0, 2.0, 3.0, 4.0]);
+    Ok(())
+}
+
+#[test]
+fn test_if() -> Result<(), Box<dyn Error>> {
+    let source = "O1 if [#1 EQ 1]
+        G0 X1
+        O1 elseif [#1 EQ 2]
+        G0 X2
+        O1 elseif [#1 EQ 3]
+        G0 X3
+        O1 else
+        G0 X4
+        O1 endif
+        G0 X10";
+    for (first, expected) in [(1.0, 1.0), (2.0, 2.0), (3.0, 3.0), (5.0, 4.0)] {
+        assert_eq!(run(first, source)?, [expected, 10.0]);
+    }
+
+    // without else nothing runs
+    let source = "O1 if [#1 EQ 1]
+        G0 X1
+        O1 elseif [#1 EQ 2]
+        G0 X2
+        O1 endif";
+    assert_eq!(run(3.0, source)?, []);
+    assert_eq!(run(2.0, source)?, [2.0]);
+    Ok(())
+}
+
+#[test]
+fn test_break_continue() -> Result<(), Box<dyn Error>> {
+    // skips 2 and stops after 4
+    let source = "O1 while [1]
+        #1 = [#1 + 1]
+        O2 if [#1 EQ 2]
+        O1 continue
+        O2 endif
+        G0 X#1
+        O3 if [#1 GE 4]
+        O1 break
+        O3 endif
+        O1 endwhile
+        G0 X10";
+    assert_eq!(run(0.0, source)?, [1.0, 3.0, 4.0, 10.0]);
+
+    // continue in a do loop tests its condition, break leaves a repeat
+    let source = "O1 do
+        #1 = [#1 + 1]
+        O1 continue
+        G0 X1
+        O1 while [#1 LT 3]
+        O2 repeat [5]
+        G0 X#1
+        O2 break
+        O2 endrepeat";
+    assert_eq!(run(0.0, source)?, [3.0]);
+    Ok(())
+}
+
+#[test]
+fn test_program_end() -> Result<(), Box<dyn Error>> {
+    // nothing runs after M2, even in a loop
+    let source = "O1 while [1]
+        G0 X#1
+        #1 = [#1 + 1]
+        O2 if [#1 GT 2]
+        M2
+        O2 endif
+        O1 endwhile
+        G0 X10";
+    assert_eq!(run(0.0, source)?, [0.0, 1.0, 2.0]);
+    Ok(())
+}
+
+#[test]
+fn test_sub_skipped() -> Result<(), Box<dyn Error>> {
+    let source = "O100 sub
+        G0 X1
+        O100 return
+        O100 endsub
+        G0 X2";
+    assert_eq!(run(0.0, source)?, [2.0]);
+    Ok(())
+}
+
+#[test]
+fn test_call() -> Result<(), Box<dyn Error>> {
+    // the arguments and named locals are in a new frame, globals are shared
+    let source = "#<x> = 1
+        #31 = 0
+        O100 sub
+        #<x> = 5
+        #31 = [#1 * 2]
+        #<_shared> = #2
+        G0 X[#1 + #2]
+        O100 endsub
+        O100 call [1.5] [#1 + 1]
+        G0 X#1
+        G0 X#<x>
+        G0 X#31
+        G0 X#<_shared>";
+    assert_eq!(run(7.0, source)?, [9.5, 7.0, 1.0, 3.0, 8.0]);
+    Ok(())
+}
+
+#[test]
+fn test_call_value() -> Result<(), Box<dyn Error>> {
+    let source = "O200 sub
+        O201 if [#1 GT 0]
+        O200 return [#1 * 2]
+        O201 endif
+        O200 endsub [-1]
+        O200 call [#1]
+        G0 X#<_value>
+        G0 Y0 X#<_value_returned>
+        O300 sub
+        O300 endsub
+        O300 call
+        G0 X#<_value_returned>";
+    assert_eq!(run(3.0, source)?, [6.0, 1.0, 0.0]);
+    assert_eq!(run(0.0, source)?, [-1.0, 1.0, 0.0]);
+
+    // recursive, each call has its own #1
+    let source = "O1 call [#1]
+        G0 X#<_value>
+        O1 sub
+        O2 if [#1 LE 1]
+        O1 return [1]
+        O2 endif
+        O1 call [#1 - 1]
+        O1 return [#1 * #<_value>]
+        O1 endsub";
+    assert_eq!(run(5.0, source)?, [120.0]);
+
+    // each call counts the iterations of its own repeat loop
+    let source = "#<_count> = 0
+        O1 sub
+        O2 repeat [2]
+        #<_count> = [#<_count> + 1]
+        O3 if [#1 GT 0]
+        O1 call [#1 - 1]
+        O3 endif
+        O2 endrepeat
+        O1 endsub
+        O1 call [#1]
+        G0 X#<_count>";
+    assert_eq!(run(2.0, source)?, [14.0]);
+    Ok(())
+}
+
+#[test]
+fn test_local_params() {
+    let mut interpreter = Interpreter::default();
+    let source = "#1 = 1
+        #<x> = 2
+        O1 sub
+        #1 = 10
+        #<x> = 20
+        O1 endsub
+        O1 call";
+    let mut program = Program::new(blocks(source)).unwrap();
+    interpreter.interpret_program(&mut program).unwrap();
+    assert_eq!(interpreter.get_numbered_param(1), Some(1.0));
+    assert_eq!(interpreter.get_local_param("x"), Some(2.0));
+}
+
+#[test]
+fn test_control_errors() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    for (source, error) in [
+        ("O1 endif", ControlError::NotOpened(1)),
+        ("O1 while [1]\nO1 endif", ControlError::NotOpened(1)),
+        ("O1 if [1]", ControlError::NotClosed(1)),
+        (
+            "O1 if [1]\nO2 while [1]\nO1 endif",
+            ControlError::NotClosed(2),
+        ),
+        (
+            "O1 if [1]\nO1 else\nO1 else\nO1 endif",
+            ControlError::AfterElse(1),
+        ),
+        (
+            "O1 if [1]\nO1 else\nO1 elseif [1]\nO1 endif",
+            ControlError::AfterElse(1),
+        ),
+        ("O1 break", ControlError::NotInLoop(1)),
+        (
+            "O1 while [1]\nO2 continue\nO1 endwhile",
+            ControlError::NotInLoop(2),
+        ),
+        ("O1 return", ControlError::NotInSub(1)),
+        ("O1 call [1]", ControlError::UnknownSub(1)),
+    ] {
+        let program = Program::new(blocks(source));
+        assert_eq!(program.unwrap_err(), error, "{source}");
+    }
+
+    let source = "O1 repeat [1.5]\nO1 endrepeat";
+    let mut program = Program::new(blocks(source)).unwrap();
+    assert_eq!(
+        interpreter.interpret_program(&mut program),
+        Err(ControlError::InvalidRepeat(1.5).into())
+    );
+
+    // O words only run in a program
+    let block = blocks("O1 if [1]").pop().unwrap();
+    assert_eq!(
+        interpreter.interpret_block(block),
+        Err(InterpretError::Control(ControlError::NoProgram))
+    );
+    Ok(())
+}
@@ -0,0 +1,255 @@
+use super::{
+    assert_position, interpret_error, moves, single_move, try_interpret, try_interpret_block,
+};
+use crate::{
+    interpret::{
+        cutter_comp::CompError,
+        interpreter::{InterpretError, InterpretValue},
+        model_state::CutterComp,
+        motion::{Move, MoveKind},
+        tool_table::Tool,
+    },
+    Interpreter,
+};
+use alloc::boxed::Box;
+use core::error::Error;
+
+extern crate std;
+
+#[track_caller]
+fn comp_error(interpreter: &mut Interpreter, input: &[u8]) -> CompError {
+    use crate::{gcode::Block, parser::Input, GcodeParser as _};
+    let block = Block::parse(Input::new(input, interpreter)).unwrap().1;
+    match interpreter.interpret_block(block) {
+        Err(InterpretError::CutterComp(err)) => err,
+        other => panic!("expected a cutter compensation error, got {:?}", other),
+    }
+}
+
+#[track_caller]
+fn assert_linear(mv: &Move, to: [f64; 3]) {
+    assert_eq!(mv.kind, MoveKind::Linear);
+    assert_position(&mv.to, to);
+}
+
+#[track_caller]
+fn assert_arc(mv: &Move, center: [f64; 3], turns: i32, to: [f64; 3]) {
+    let MoveKind::Arc(arc) = &mv.kind else {
+        panic!("expected an arc, got {:?}", mv.kind);
+    };
+    assert_position(&arc.center, center);
+    assert_eq!(arc.turns, turns);
+    assert_position(&mv.to, to);
+}
+
+/// Below the origin, ready for an entry move up to it
+fn interpreter_below_origin() -> Interpreter {
+    let mut interpreter = Interpreter::default();
+    try_interpret(&mut interpreter, b"F600").unwrap();
+    try_interpret(&mut interpreter, b"G0 X0 Y-5").unwrap();
+    interpreter
+}
+
+#[test]
+fn test_cutter_comp_lines() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_below_origin();
+    try_interpret(&mut interpreter, b"G41.1 D2")?;
+    assert_eq!(interpreter.get_model_state().cutter_comp, CutterComp::Left);
+    assert_eq!(interpreter.get_model_state().cutter_comp_radius, 1.0);
+
+    // the entry move is held back until the next move decides its corner
+    let entry = moves(try_interpret(&mut interpreter, b"G1 X0 Y0")?);
+    assert!(entry.is_empty());
+
+    // convex corner: around the programmed corner
+    let events = moves(try_interpret(&mut interpreter, b"G1 X10 Y0")?);
+    assert_eq!(events.len(), 2);
+    assert_linear(&events[0], [-1.0, 0.0, 0.0]);
+    assert_arc(&events[1], [0.0, 0.0, 0.0], -1, [0.0, 1.0, 0.0]);
+
+    // concave corner: to where the offset lines meet
+    let events = moves(try_interpret(&mut interpreter, b"G1 X10 Y10")?);
+    assert_eq!(events.len(), 1);
+    assert_linear(&events[0], [9.0, 1.0, 0.0]);
+
+    // the position stays the programmed one
+    assert_position(
+        &interpreter.get_model_state().abs_position,
+        [10.0, 10.0, 0.0],
+    );
+
+    // G40 ends the last move a radius from its end point
+    let events = moves(try_interpret(&mut interpreter, b"G40")?);
+    assert_eq!(events.len(), 1);
+    assert_linear(&events[0], [9.0, 10.0, 0.0]);
+    assert_eq!(interpreter.get_model_state().cutter_comp, CutterComp::Off);
+
+    // exit move, uncompensated
+    let mv = single_move(try_interpret(&mut interpreter, b"G1 X20 Y20")?);
+    assert_linear(&mv, [20.0, 20.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn test_cutter_comp_right() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_below_origin();
+    try_interpret(&mut interpreter, b"G42.1 D2")?;
+    try_interpret(&mut interpreter, b"G1 X0 Y0")?;
+
+    // concave on the right, the entry move starts uncompensated so it is slanted
+    let events = moves(try_interpret(&mut interpreter, b"G1 X10 Y0")?);
+    assert_eq!(events.len(), 1);
+    assert_linear(&events[0], [0.8, -1.0, 0.0]);
+
+    let events = moves(try_interpret(&mut interpreter, b"G40")?);
+    assert_linear(&events[0], [10.0, -1.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn test_cutter_comp_arcs() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_below_origin();
+    try_interpret(&mut interpreter, b"G41.1 D2")?;
+    try_interpret(&mut interpreter, b"G1 X0 Y0")?;
+    try_interpret(&mut interpreter, b"G1 X10 Y0")?;
+
+    // half circle dipping below, the tool is on its inside
+    let events = moves(try_interpret(&mut interpreter, b"G3 X20 Y0 R5")?);
+    assert_eq!(events.len(), 2);
+    assert_linear(&events[0], [10.0, 1.0, 0.0]);
+    assert_arc(&events[1], [10.0, 0.0, 0.0], -1, [11.0, 0.0, 0.0]);
+
+    let events = moves(try_interpret(&mut interpreter, b"G40")?);
+    assert_eq!(events.len(), 1);
+    assert_arc(&events[0], [15.0, 0.0, 0.0], 1, [19.0, 0.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn test_cutter_comp_line_into_arc() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_below_origin();
+    try_interpret(&mut interpreter, b"G41.1 D2")?;
+    try_interpret(&mut interpreter, b"G1 X0 Y0")?;
+    try_interpret(&mut interpreter, b"G1 X10 Y0")?;
+
+    // concave: the offset line meets the offset circle of radius 6 around (15, 0)
+    let events = moves(try_interpret(&mut interpreter, b"G2 X20 Y0 R5")?);
+    assert_eq!(events.len(), 1);
+    assert_linear(&events[0], [15.0 - 35f64.sqrt(), 1.0, 0.0]);
+
+    let events = moves(try_interpret(&mut interpreter, b"G40")?);
+    assert_arc(&events[0], [15.0, 0.0, 0.0], -1, [21.0, 0.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn test_cutter_comp_queued() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_below_origin();
+    try_interpret(&mut interpreter, b"G41.1 D2")?;
+    try_interpret(&mut interpreter, b"G1 X0 Y0")?;
+    try_interpret(&mut interpreter, b"G1 X10 Y0")?;
+
+    // a plunge waits for the corner, then runs where the previous move ended
+    let value = try_interpret(&mut interpreter, b"G1 Z-1")?;
+    assert_eq!(value, InterpretValue::Motion(std::vec![]));
+    let events = moves(try_interpret(&mut interpreter, b"G1 X10 Y10")?);
+    assert_eq!(events.len(), 2);
+    assert_linear(&events[0], [9.0, 1.0, 0.0]);
+    assert_linear(&events[1], [9.0, 1.0, -1.0]);
+
+    let events = moves(try_interpret(&mut interpreter, b"G40")?);
+    assert_linear(&events[0], [9.0, 10.0, -1.0]);
+    Ok(())
+}
+
+#[test]
+fn test_cutter_comp_tool_table() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_below_origin();
+    interpreter.tool_table_mut().set(
+        3,
+        Tool {
+            diameter: 4.0,
+            ..Tool::default()
+        },
+    );
+
+    try_interpret(&mut interpreter, b"G41 D3")?;
+    assert_eq!(interpreter.get_model_state().cutter_comp_radius, 2.0);
+    assert_eq!(
+        comp_error(&mut interpreter, b"G42 D3"),
+        CompError::AlreadyOn
+    );
+
+    // nothing held back
+    assert_eq!(
+        try_interpret(&mut interpreter, b"G40")?,
+        InterpretValue::Other
+    );
+
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G42 D7"),
+        InterpretError::UnknownTool(7)
+    );
+    assert_eq!(
+        comp_error(&mut interpreter, b"G41 D1.5"),
+        CompError::InvalidD(1.5)
+    );
+    assert_eq!(
+        comp_error(&mut interpreter, b"G41.1 D-1"),
+        CompError::InvalidD(-1.0)
+    );
+
+    // without D, the tool in the spindle
+    try_interpret_block(&mut interpreter, b"M6 T3")?;
+    try_interpret(&mut interpreter, b"G41")?;
+    assert_eq!(interpreter.get_model_state().cutter_comp_radius, 2.0);
+    try_interpret(&mut interpreter, b"G40")?;
+
+    // the diameter of G41.1 is in the selected unit
+    try_interpret_block(&mut interpreter, b"G20 G42.1 D0.1")?;
+    assert!((interpreter.get_model_state().cutter_comp_radius - 1.27).abs() < 1e-6);
+    assert_eq!(interpreter.get_model_state().cutter_comp, CutterComp::Right);
+    Ok(())
+}
+
+#[test]
+fn test_cutter_comp_errors() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_below_origin();
+    try_interpret(&mut interpreter, b"G41.1 D2")?;
+    assert_eq!(
+        comp_error(&mut interpreter, b"G2 X10 Y-5 R5"),
+        CompError::EntryArc
+    );
+    assert_eq!(
+        comp_error(&mut interpreter, b"G1 X0 Y-4.5"),
+        CompError::EntryTooShort
+    );
+    assert_eq!(comp_error(&mut interpreter, b"G28"), CompError::NotAllowed);
+    assert_eq!(
+        comp_error(&mut interpreter, b"G53 G0 X0"),
+        CompError::NotAllowed
+    );
+    assert_eq!(comp_error(&mut interpreter, b"G18"), CompError::NotAllowed);
+    try_interpret(&mut interpreter, b"G17")?;
+
+    try_interpret(&mut interpreter, b"G1 X0 Y0")?;
+    try_interpret(&mut interpreter, b"G1 X10 Y0")?;
+    // the tool does not fit inside the arc
+    assert_eq!(
+        comp_error(&mut interpreter, b"G3 X11.6 Y0 R0.8"),
+        CompError::ArcTooSmall
+    );
+    // the next move is shorter than the radius, the corner would cut into it
+    assert_eq!(
+        comp_error(&mut interpreter, b"G1 X10 Y0.5"),
+        CompError::Gouge
+    );
+
+    let mut interpreter = interpreter_below_origin();
+    try_interpret(&mut interpreter, b"G19")?;
+    assert_eq!(
+        comp_error(&mut interpreter, b"G41.1 D2"),
+        CompError::UnsupportedPlane
+    );
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use super::{interpret_error, single_move, try_interpret, try_interpret_block};
+use crate::{
+    interpret::{
+        interpreter::InterpretError,
+        model_state::{FeedMode, MmSec},
+    },
+    Interpreter,
+};
+use alloc::boxed::Box;
+use core::{error::Error, f64::consts::PI};
+
+extern crate std;
+
+#[track_caller]
+fn assert_feedrate(actual: MmSec, expected: f64) {
+    assert!(
+        (actual.0 - expected).abs() < 1e-6,
+        "feedrate {} != {}",
+        actual.0,
+        expected
+    );
+}
+
+#[test]
+fn test_inverse_time() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret(&mut interpreter, b"G93")?;
+    assert_eq!(
+        interpreter.get_model_state().feed_mode,
+        FeedMode::InverseTime
+    );
+
+    // F30 is two seconds for the move
+    let mut values = try_interpret_block(&mut interpreter, b"G1 X3 Y4 F30")?;
+    let mv = single_move(values.pop().unwrap());
+    assert_feedrate(mv.feedrate, 2.5);
+
+    // half a circle with a radius of 1 in a second
+    let mut values = try_interpret_block(&mut interpreter, b"G2 X5 Y4 I1 J0 F60")?;
+    let mv = single_move(values.pop().unwrap());
+    assert_feedrate(mv.feedrate, PI);
+
+    // helix, a full turn around the Z axis
+    let mut values = try_interpret_block(&mut interpreter, b"G3 X5 Y4 Z3 I-1 J0 P1 F60")?;
+    let mv = single_move(values.pop().unwrap());
+    assert_feedrate(mv.feedrate, (4.0 * PI * PI + 9.0f64).sqrt());
+
+    // rapids need no F
+    let mut values = try_interpret_block(&mut interpreter, b"G0 X0 Y0 Z0")?;
+    assert_eq!(single_move(values.pop().unwrap()).feedrate, MmSec(0.0));
+
+    // back to units per minute, with the last F
+    try_interpret(&mut interpreter, b"G94")?;
+    assert_feedrate(interpreter.get_model_state().feedrate, 1.0);
+    Ok(())
+}
+
+#[test]
+fn test_inverse_time_needs_f() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret_block(&mut interpreter, b"G93 G1 X1 F60")?;
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G1 X2"),
+        InterpretError::MissingWord('F')
+    );
+    // F on a line of its own only applies to that line
+    try_interpret_block(&mut interpreter, b"F60")?;
+    assert_eq!(
+        interpret_error(&mut interpreter, b"X2"),
+        InterpretError::MissingWord('F')
+    );
+    try_interpret(&mut interpreter, b"F0")?;
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G1 X2"),
+        InterpretError::ZeroFeedrate
+    );
+    Ok(())
+}
+
+#[test]
+fn test_units_per_revolution() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret_block(&mut interpreter, b"G95 F0.1 S1200")?;
+    assert_eq!(
+        interpreter.get_model_state().feed_mode,
+        FeedMode::UnitsPerRevolution
+    );
+    assert_eq!(interpreter.get_model_state().spindle_speed, 1200.0);
+    // 0.1 mm per revolution at 20 revolutions per second
+    let mv = single_move(try_interpret(&mut interpreter, b"G1 X1")?);
+    assert_feedrate(mv.feedrate, 2.0);
+
+    // follows the spindle speed and the unit
+    try_interpret_block(&mut interpreter, b"S600 G20")?;
+    assert_feedrate(interpreter.get_model_state().feedrate, 25.4);
+
+    try_interpret(&mut interpreter, b"S0")?;
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G1 X2"),
+        InterpretError::ZeroFeedrate
+    );
+    Ok(())
+}
+
+#[test]
+fn test_feed_mode_errors() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    assert_eq!(
+        interpret_error(&mut interpreter, b"S-1"),
+        InterpretError::NegativeSpindleSpeed(-1.0)
+    );
+    try_interpret_block(&mut interpreter, b"G93 F60")?;
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G81 X1 Z-1 R1"),
+        InterpretError::InverseTimeFeed
+    );
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G38.2 Z-10"),
+        InterpretError::InverseTimeFeed
+    );
+    Ok(())
+}
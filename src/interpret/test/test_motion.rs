@@ -0,0 +1,398 @@
+use super::{
+    assert_position, interpret_error, moves, single_move, try_interpret, try_interpret_block,
+};
+use crate::{
+    interpret::{
+        interpreter::{InterpretError, InterpretValue},
+        model_state::{
+            Coolant, DistanceMode, FeedMode, MmSec, MotionMode, PathMode, Plane, Position,
+            SpindleDirection, Workspace,
+        },
+        motion::{MotionEvent, Move, MoveKind},
+    },
+    Interpreter,
+};
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::error::Error;
+
+extern crate std;
+
+#[test]
+fn test_rapid() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    let mv = single_move(try_interpret(&mut interpreter, b"G0 X10 Y-5")?);
+    assert_eq!(mv.kind, MoveKind::Rapid);
+    assert_eq!(mv.feedrate, MmSec(0.0));
+    assert_position(&mv.to, [10.0, -5.0, 0.0]);
+    assert_position(
+        &interpreter.get_model_state().abs_position,
+        [10.0, -5.0, 0.0],
+    );
+
+    // no axes, no motion
+    assert_eq!(
+        try_interpret(&mut interpreter, b"G0")?,
+        InterpretValue::Motion(alloc::vec![])
+    );
+    Ok(())
+}
+
+#[test]
+fn test_linear() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret(&mut interpreter, b"F600")?;
+    let mv = single_move(try_interpret(&mut interpreter, b"G1 X1 Y2 Z3")?);
+    assert_eq!(mv.kind, MoveKind::Linear);
+    assert_eq!(mv.feedrate, MmSec(10.0));
+    assert_position(&mv.to, [1.0, 2.0, 3.0]);
+
+    // axes without a word stay put
+    let mv = single_move(try_interpret(&mut interpreter, b"G1 Y-2")?);
+    assert_position(&mv.to, [1.0, -2.0, 3.0]);
+    Ok(())
+}
+
+/// Moves of every command of a block
+fn block_moves(interpreter: &mut Interpreter, input: &[u8]) -> Result<Vec<Move>, Box<dyn Error>> {
+    Ok(try_interpret_block(interpreter, input)?
+        .into_iter()
+        .filter(|value| matches!(value, InterpretValue::Motion(_)))
+        .flat_map(moves)
+        .collect())
+}
+
+#[test]
+fn test_axis_words_split_by_feed() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    let moves = block_moves(&mut interpreter, b"G1 X10 F300 Y5")?;
+    assert_eq!(moves.len(), 1, "{:?}", moves);
+    assert_position(&moves[0].to, [10.0, 5.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn test_words_in_any_order() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    for input in [&b"G1 F300 X10 Y5"[..], b"Y5 F300 X10 G1", b"F300 G1 Y5 X10"] {
+        try_interpret_block(&mut interpreter, b"G0 X0 Y0")?;
+        let moves = block_moves(&mut interpreter, input)?;
+        assert_eq!(moves.len(), 1, "{:?}", moves);
+        assert_eq!(moves[0].kind, MoveKind::Linear);
+        assert_eq!(moves[0].feedrate, MmSec(5.0));
+        assert_position(&moves[0].to, [10.0, 5.0, 0.0]);
+    }
+
+    // without axis words G1 only selects the motion mode
+    try_interpret(&mut interpreter, b"G0 X0")?;
+    assert_eq!(block_moves(&mut interpreter, b"G1 F600")?, []);
+    let mv = single_move(try_interpret(&mut interpreter, b"X1")?);
+    assert_eq!(mv.kind, MoveKind::Linear);
+    Ok(())
+}
+
+#[test]
+fn test_linear_zero_feedrate() {
+    use crate::{gcode::Command, parser::Input, GcodeParser as _};
+    let mut interpreter = Interpreter::default();
+    let command = Command::parse(Input::new(b"G1 X1", &interpreter))
+        .unwrap()
+        .1;
+    assert_eq!(
+        interpreter.interpret(command),
+        Err(InterpretError::ZeroFeedrate)
+    );
+    assert_position(&interpreter.get_model_state().abs_position, [0.0; 3]);
+}
+
+#[test]
+fn test_distance_mode() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    assert_eq!(
+        interpreter.get_model_state().distance_mode,
+        DistanceMode::Absolute
+    );
+    try_interpret_block(&mut interpreter, b"G0 X1 Y1 Z1")?;
+
+    try_interpret(&mut interpreter, b"G91")?;
+    assert_eq!(
+        interpreter.get_model_state().distance_mode,
+        DistanceMode::Incremental
+    );
+    let mv = single_move(try_interpret(&mut interpreter, b"G0 X2 Z-1")?);
+    assert_position(&mv.to, [3.0, 1.0, 0.0]);
+    let mv = single_move(try_interpret(&mut interpreter, b"G0 X2")?);
+    assert_position(&mv.to, [5.0, 1.0, 0.0]);
+
+    try_interpret(&mut interpreter, b"G90")?;
+    let mv = single_move(try_interpret(&mut interpreter, b"G0 X2")?);
+    assert_position(&mv.to, [2.0, 1.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn test_distance_mode_in_block() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret(&mut interpreter, b"G0 X1")?;
+    // G91 takes effect before the motion of its block
+    let values = try_interpret_block(&mut interpreter, b"G0 X1 G91")?;
+    assert_position(&single_move(values[1].clone()).to, [2.0, 0.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn test_inches() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret_block(&mut interpreter, b"G20 G0 X1 Y-0.5")?;
+    assert_position(
+        &interpreter.get_model_state().abs_position,
+        [25.4, -12.7, 0.0],
+    );
+    try_interpret_block(&mut interpreter, b"G91 G0 Z0.1")?;
+    assert_position(
+        &interpreter.get_model_state().abs_position,
+        [25.4, -12.7, 2.54],
+    );
+    Ok(())
+}
+
+#[test]
+fn test_incremental_ignores_offsets() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret_block(&mut interpreter, b"G10 L2 P1 X100 G54")?;
+    let mv = single_move(try_interpret(&mut interpreter, b"G0 X1")?);
+    assert_position(&mv.to, [101.0, 0.0, 0.0]);
+
+    try_interpret(&mut interpreter, b"G91")?;
+    let mv = single_move(try_interpret(&mut interpreter, b"G0 X1")?);
+    assert_position(&mv.to, [102.0, 0.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn test_incremental_arc() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret_block(&mut interpreter, b"F600 G0 X10 G91")?;
+    let mv = single_move(try_interpret(&mut interpreter, b"G3 X-10 Y10 J10")?);
+    let MoveKind::Arc(arc) = &mv.kind else {
+        panic!("expected an arc, got {:?}", mv.kind);
+    };
+    assert_position(&arc.center, [10.0, 10.0, 0.0]);
+    assert_position(&mv.to, [0.0, 10.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn test_g53() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret_block(&mut interpreter, b"G10 L2 P2 X10 Z-50 G55")?;
+    try_interpret(&mut interpreter, b"G0 X1 Z5")?;
+    assert_position(
+        &interpreter.get_model_state().abs_position,
+        [11.0, 0.0, -45.0],
+    );
+    try_interpret(&mut interpreter, b"G92 Z0")?;
+
+    // retract to the machine top, the other axes stay put
+    let values = try_interpret_block(&mut interpreter, b"G53 G0 Z0")?;
+    assert_eq!(values[0], InterpretValue::Other);
+    let mv = single_move(values[1].clone());
+    assert_eq!(mv.kind, MoveKind::Rapid);
+    assert_position(&mv.to, [11.0, 0.0, 0.0]);
+
+    // G53 may come after the motion on the line, and works with G1
+    let values = try_interpret_block(&mut interpreter, b"F60 G1 X-3 G53")?;
+    assert_position(&single_move(values[2].clone()).to, [-3.0, 0.0, 0.0]);
+
+    // not modal, the next line is in the active workspace with the G92 offset
+    let mv = single_move(try_interpret_block(&mut interpreter, b"G0 X0 Z0")?.remove(0));
+    assert_position(&mv.to, [10.0, 0.0, -45.0]);
+    assert_eq!(
+        interpreter.get_model_state().workspace,
+        crate::interpret::model_state::Workspace::G55
+    );
+    Ok(())
+}
+
+#[test]
+fn test_g53_inches() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    let values = try_interpret_block(&mut interpreter, b"G20 G53 G0 X1")?;
+    assert_position(&single_move(values[2].clone()).to, [25.4, 0.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn test_g53_errors() {
+    use crate::{gcode::Block, parser::Input, GcodeParser as _};
+
+    for (input, expected) in [
+        (&b"G91 G53 G0 Z0"[..], InterpretError::G53Incremental),
+        (b"F60 G53 G2 X2 I1", InterpretError::G53NotStraight),
+        (b"G53 G90", InterpretError::G53NotStraight),
+    ] {
+        let mut interpreter = Interpreter::default();
+        let block = Block::parse(Input::new(input, &interpreter)).unwrap().1;
+        assert_eq!(interpreter.interpret_block(block), Err(expected));
+    }
+}
+
+#[test]
+fn test_g53_single_command() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret_block(&mut interpreter, b"G10 L2 P2 X10 G55")?;
+    // a G53 on its own has no motion to apply to, the next command is a block of its own
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G53"),
+        InterpretError::G53NotStraight
+    );
+    let mv = single_move(try_interpret(&mut interpreter, b"G0 X1")?);
+    assert_position(&mv.to, [11.0, 0.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn test_g28_1_g30_1() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret_block(&mut interpreter, b"G10 L2 P1 X100 G54 G0 X1 Y2 Z3")?;
+    try_interpret(&mut interpreter, b"G28.1")?;
+    // stored in machine coordinates
+    assert_eq!(interpreter.get_numbered_param(5161), Some(101.0));
+    assert_eq!(interpreter.get_numbered_param(5162), Some(2.0));
+    assert_eq!(interpreter.get_numbered_param(5163), Some(3.0));
+    assert_eq!(interpreter.get_numbered_param(5181), None);
+
+    // stored before the motion of its block
+    try_interpret_block(&mut interpreter, b"G0 Z-1 G30.1")?;
+    assert_eq!(interpreter.get_numbered_param(5183), Some(3.0));
+    try_interpret(&mut interpreter, b"G30.1")?;
+    assert_eq!(interpreter.get_numbered_param(5183), Some(-1.0));
+    assert_eq!(interpreter.get_numbered_param(5163), Some(3.0));
+    Ok(())
+}
+
+#[test]
+fn test_g28() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret_block(&mut interpreter, b"#5161=10 #5162=20 #5163=30")?;
+    try_interpret(&mut interpreter, b"G0 X1 Y2 Z3")?;
+
+    // without axis words, every axis goes straight home
+    let mvs = moves(try_interpret(&mut interpreter, b"G28")?);
+    assert_eq!(mvs.len(), 1);
+    assert_eq!(mvs[0].kind, MoveKind::Rapid);
+    assert_position(&mvs[0].to, [10.0, 20.0, 30.0]);
+    assert_position(
+        &interpreter.get_model_state().abs_position,
+        [10.0, 20.0, 30.0],
+    );
+    Ok(())
+}
+
+#[test]
+fn test_g30_intermediate() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret_block(&mut interpreter, b"#5181=-1 #5183=-2 G10 L2 P1 Z50 G54")?;
+    try_interpret(&mut interpreter, b"G0 X1 Y2 Z3")?;
+
+    // up to the intermediate Z in program coordinates, then only Z goes home
+    let mvs = moves(try_interpret(&mut interpreter, b"G30 Z10")?);
+    assert_eq!(mvs.len(), 2);
+    assert!(mvs.iter().all(|mv| mv.kind == MoveKind::Rapid));
+    assert_position(&mvs[0].to, [1.0, 2.0, 60.0]);
+    assert_position(&mvs[1].to, [1.0, 2.0, -2.0]);
+
+    // the intermediate point follows the distance mode
+    try_interpret_block(&mut interpreter, b"G91 G0 X0")?;
+    let mvs = moves(try_interpret(&mut interpreter, b"G30 X5 Y0")?);
+    assert_position(&mvs[0].to, [6.0, 2.0, -2.0]);
+    assert_position(&mvs[1].to, [-1.0, 0.0, -2.0]);
+    Ok(())
+}
+
+#[test]
+fn test_path_mode() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret(&mut interpreter, b"F600")?;
+    let blend = PathMode::Blend {
+        tolerance: None,
+        naive_cam_tolerance: None,
+    };
+    assert_eq!(interpreter.get_model_state().path_mode, blend);
+    let mv = single_move(try_interpret(&mut interpreter, b"G1 X1")?);
+    assert_eq!(mv.path_mode, blend);
+
+    // the mode is set before the motion of the same block
+    let mut values = try_interpret_block(&mut interpreter, b"G1 X2 G61")?;
+    let mv = single_move(values.pop().unwrap());
+    assert_eq!(mv.path_mode, PathMode::ExactPath);
+    try_interpret(&mut interpreter, b"G61.1")?;
+    let mv = single_move(try_interpret(&mut interpreter, b"G0 X0")?);
+    assert_eq!(mv.path_mode, PathMode::ExactStop);
+
+    // tolerances in mm
+    try_interpret_block(&mut interpreter, b"G20 G64 P0.01 Q0.005")?;
+    let mv = single_move(try_interpret(&mut interpreter, b"G1 X1")?);
+    let PathMode::Blend {
+        tolerance: Some(tolerance),
+        naive_cam_tolerance: Some(naive_cam_tolerance),
+    } = mv.path_mode
+    else {
+        panic!("{:?}", mv.path_mode);
+    };
+    assert!((tolerance - 0.254).abs() < 1e-6);
+    assert!((naive_cam_tolerance - 0.127).abs() < 1e-6);
+
+    try_interpret(&mut interpreter, b"G64")?;
+    assert_eq!(interpreter.get_model_state().path_mode, blend);
+    Ok(())
+}
+
+#[test]
+fn test_path_mode_errors() {
+    let mut interpreter = Interpreter::default();
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G64 Q0.1"),
+        InterpretError::MissingWord('P')
+    );
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G64 P-0.1"),
+        InterpretError::NegativeTolerance(-0.1)
+    );
+}
+
+#[test]
+fn test_program_stops() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    for input in [&b"M0"[..], b"M60"] {
+        assert_eq!(
+            try_interpret(&mut interpreter, input)?,
+            InterpretValue::Motion(vec![MotionEvent::Pause])
+        );
+    }
+    // M1 only pauses with the optional stop switch on
+    assert_eq!(
+        try_interpret(&mut interpreter, b"M1")?,
+        InterpretValue::Other
+    );
+    interpreter.set_optional_stop(true);
+    assert_eq!(
+        try_interpret(&mut interpreter, b"M1")?,
+        InterpretValue::Motion(vec![MotionEvent::Pause])
+    );
+
+    // the end resets the modes, after the motion of its block
+    try_interpret_block(&mut interpreter, b"G55 G18 G91 G93 G92 X1 M3 M8")?;
+    let values = try_interpret_block(&mut interpreter, b"M2 G0 X1")?;
+    assert_position(&single_move(values[0].clone()).to, [1.0, 0.0, 0.0]);
+    assert_eq!(values[1], InterpretValue::Motion(vec![MotionEvent::End]));
+    let state = interpreter.get_model_state();
+    assert_eq!(state.workspace, Workspace::G54);
+    assert_eq!(state.plane, Plane::XY);
+    assert_eq!(state.distance_mode, DistanceMode::Absolute);
+    assert_eq!(state.feed_mode, FeedMode::UnitsPerMinute);
+    assert_eq!(state.axis_offsets, Position::default());
+    assert_eq!(state.motion_mode, MotionMode::Linear);
+    assert_eq!(state.spindle_direction, SpindleDirection::Stopped);
+    assert_eq!(state.coolant, Coolant::default());
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use super::{assert_position, single_move, try_interpret, try_interpret_block};
+use crate::{
+    gcode::Command,
+    interpret::{
+        interpreter::InterpretError,
+        model_state::{MmSec, Position},
+        motion::MoveKind,
+        probe::{Probe, ProbeMove, ProbeTrigger},
+    },
+    parser::Input,
+    GcodeParser as _, Interpreter, NUM_AXES,
+};
+use alloc::{boxed::Box, rc::Rc, vec::Vec};
+use core::{cell::RefCell, error::Error};
+
+extern crate std;
+
+/// Trips `fraction` of the way along each move, records the moves it was asked for
+#[derive(Debug)]
+struct MockProbe {
+    fraction: Option<f64>,
+    moves: Rc<RefCell<Vec<ProbeMove>>>,
+}
+
+impl Probe for MockProbe {
+    fn probe(&mut self, mv: &ProbeMove) -> Option<Position<NUM_AXES>> {
+        self.moves.borrow_mut().push(mv.clone());
+        let fraction = self.fraction?;
+        let mut position = mv.from;
+        for idx in 0..NUM_AXES {
+            position[idx] += (mv.to[idx] - mv.from[idx]) * fraction;
+        }
+        Some(position)
+    }
+}
+
+fn interpreter_with_probe(fraction: Option<f64>) -> (Interpreter, Rc<RefCell<Vec<ProbeMove>>>) {
+    let moves = Rc::new(RefCell::new(Vec::new()));
+    let mut interpreter = Interpreter::default();
+    interpreter.set_probe(Box::new(MockProbe {
+        fraction,
+        moves: moves.clone(),
+    }));
+    try_interpret(&mut interpreter, b"F60").unwrap();
+    (interpreter, moves)
+}
+
+#[track_caller]
+fn probe_error(interpreter: &mut Interpreter, input: &[u8]) -> InterpretError {
+    let command = Command::parse(Input::new(input, interpreter)).unwrap().1;
+    interpreter.interpret(command).unwrap_err()
+}
+
+#[test]
+fn test_g38_2_trips() -> Result<(), Box<dyn Error>> {
+    let (mut interpreter, moves) = interpreter_with_probe(Some(0.25));
+    try_interpret_block(&mut interpreter, b"G10 L2 P1 Z-100 G54")?;
+
+    let mv = single_move(try_interpret(&mut interpreter, b"G38.2 Z-10")?);
+    assert_eq!(mv.kind, MoveKind::Probe(ProbeTrigger::Contact));
+    assert_eq!(mv.feedrate, MmSec(1.0));
+    assert_position(&mv.to, [0.0, 0.0, -27.5]);
+    assert_position(
+        &interpreter.get_model_state().abs_position,
+        [0.0, 0.0, -27.5],
+    );
+
+    let moves = moves.borrow();
+    assert_eq!(moves.len(), 1);
+    assert_position(&moves[0].from, [0.0, 0.0, 0.0]);
+    assert_position(&moves[0].to, [0.0, 0.0, -110.0]);
+    assert_eq!(moves[0].trigger, ProbeTrigger::Contact);
+
+    // in program coordinates
+    assert_eq!(interpreter.get_numbered_param(5061), Some(0.0));
+    assert_eq!(interpreter.get_numbered_param(5063), Some(72.5));
+    assert_eq!(interpreter.get_numbered_param(5070), Some(1.0));
+    Ok(())
+}
+
+#[test]
+fn test_probe_result_inches() -> Result<(), Box<dyn Error>> {
+    let (mut interpreter, _) = interpreter_with_probe(Some(0.5));
+    try_interpret_block(&mut interpreter, b"G20 G38.3 X2")?;
+    assert_eq!(interpreter.get_numbered_param(5061), Some(1.0));
+    assert_position(
+        &interpreter.get_model_state().abs_position,
+        [25.4, 0.0, 0.0],
+    );
+    Ok(())
+}
+
+#[test]
+fn test_probe_not_tripped() -> Result<(), Box<dyn Error>> {
+    // G38.3 and G38.5 end at the programmed point
+    for (input, trigger) in [
+        (&b"G38.3 X10"[..], ProbeTrigger::Contact),
+        (b"G38.5 X10", ProbeTrigger::LossOfContact),
+    ] {
+        let (mut interpreter, _) = interpreter_with_probe(None);
+        let mv = single_move(try_interpret(&mut interpreter, input)?);
+        assert_eq!(mv.kind, MoveKind::Probe(trigger));
+        assert_position(&mv.to, [10.0, 0.0, 0.0]);
+        assert_eq!(interpreter.get_numbered_param(5061), Some(10.0));
+        assert_eq!(interpreter.get_numbered_param(5070), Some(0.0));
+    }
+
+    // G38.2 and G38.4 are errors, the result is stored as the machine has moved
+    for (input, trigger) in [
+        (&b"G38.2 X10"[..], ProbeTrigger::Contact),
+        (b"G38.4 X10", ProbeTrigger::LossOfContact),
+    ] {
+        let (mut interpreter, moves) = interpreter_with_probe(None);
+        assert_eq!(
+            probe_error(&mut interpreter, input),
+            InterpretError::ProbeNotTripped
+        );
+        assert_eq!(moves.borrow()[0].trigger, trigger);
+        assert_eq!(interpreter.get_numbered_param(5061), Some(10.0));
+        assert_eq!(interpreter.get_numbered_param(5070), Some(0.0));
+        assert_position(
+            &interpreter.get_model_state().abs_position,
+            [10.0, 0.0, 0.0],
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_probe_errors() {
+    let (mut interpreter, moves) = interpreter_with_probe(Some(0.5));
+    assert_eq!(
+        probe_error(&mut interpreter, b"G38.2 X0.2 Y0.1"),
+        InterpretError::ProbeTooClose
+    );
+    try_interpret(&mut interpreter, b"F0").unwrap();
+    assert_eq!(
+        probe_error(&mut interpreter, b"G38.2 X10"),
+        InterpretError::ZeroFeedrate
+    );
+    assert!(moves.borrow().is_empty());
+
+    let mut interpreter = Interpreter::default();
+    try_interpret(&mut interpreter, b"F60").unwrap();
+    assert_eq!(
+        probe_error(&mut interpreter, b"G38.2 X10"),
+        InterpretError::NoProbe
+    );
+}
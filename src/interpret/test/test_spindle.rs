@@ -0,0 +1,172 @@
+use super::{assert_position, interpret_error, single_move, try_interpret, try_interpret_block};
+use crate::{
+    interpret::{
+        interpreter::{InterpretError, InterpretValue},
+        model_state::{DiameterMode, MmSec, SpindleDirection, SpindleMode},
+        motion::MoveKind,
+    },
+    Interpreter,
+};
+use alloc::{boxed::Box, vec::Vec};
+use core::{error::Error, f64::consts::TAU};
+
+#[track_caller]
+fn assert_rpm(interpreter: &Interpreter, expected: f64) {
+    let rpm = interpreter.get_model_state().spindle_rpm;
+    assert!(
+        (rpm - expected).abs() < 1e-6,
+        "spindle speed {} != {}",
+        rpm,
+        expected
+    );
+}
+
+#[test]
+fn test_cam_line() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    let values = try_interpret_block(&mut interpreter, b"N10 G1 X10 Y5 F300 S12000 M3 (cut)")?;
+    let motion: Vec<_> = values
+        .into_iter()
+        .filter(|value| matches!(value, InterpretValue::Motion(_)))
+        .collect();
+    let [motion] = <[_; 1]>::try_from(motion).unwrap();
+    let mv = single_move(motion);
+    assert_eq!(mv.kind, MoveKind::Linear);
+    assert_eq!(mv.feedrate, MmSec(5.0));
+    assert_position(&mv.to, [10.0, 5.0, 0.0]);
+    let state = interpreter.get_model_state();
+    assert_eq!(state.spindle_direction, SpindleDirection::Clockwise);
+    assert_rpm(&interpreter, 12000.0);
+    Ok(())
+}
+
+#[test]
+fn test_rpm() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    assert_eq!(interpreter.get_model_state().spindle_mode, SpindleMode::Rpm);
+    try_interpret(&mut interpreter, b"S1000")?;
+    assert_rpm(&interpreter, 1000.0);
+    // independent of the position
+    try_interpret(&mut interpreter, b"G0 X10")?;
+    assert_rpm(&interpreter, 1000.0);
+    Ok(())
+}
+
+#[test]
+fn test_constant_surface_speed() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret(&mut interpreter, b"G0 X10")?;
+    try_interpret_block(&mut interpreter, b"G96 D2000 S100")?;
+    assert_eq!(
+        interpreter.get_model_state().spindle_mode,
+        SpindleMode::ConstantSurfaceSpeed {
+            max_rpm: Some(2000.0)
+        }
+    );
+    // 100 m/min at a radius of 10 mm
+    assert_rpm(&interpreter, 100_000.0 / (TAU * 10.0));
+
+    // faster closer to the spindle axis, up to D
+    try_interpret_block(&mut interpreter, b"G1 X-8 F100")?;
+    assert_rpm(&interpreter, 100_000.0 / (TAU * 8.0));
+    try_interpret(&mut interpreter, b"G1 X0.5")?;
+    assert_rpm(&interpreter, 2000.0);
+
+    // the radius is in program coordinates
+    try_interpret(&mut interpreter, b"G92 X20")?;
+    assert_rpm(&interpreter, 100_000.0 / (TAU * 20.0));
+
+    // feet per minute in inches
+    try_interpret(&mut interpreter, b"G20")?;
+    assert_rpm(&interpreter, 100.0 * 304.8 / (TAU * 20.0));
+
+    // the feedrate per revolution follows
+    try_interpret_block(&mut interpreter, b"G21 G95 F0.1")?;
+    let rpm = 100_000.0 / (TAU * 20.0);
+    let feedrate = interpreter.get_model_state().feedrate.0;
+    assert!((feedrate - 0.1 * rpm / 60.0).abs() < 1e-6);
+
+    // back to a fixed speed, the S word is kept
+    try_interpret(&mut interpreter, b"G97")?;
+    assert_rpm(&interpreter, 100.0);
+    Ok(())
+}
+
+#[test]
+fn test_surface_speed_word_order() -> Result<(), Box<dyn Error>> {
+    for input in [&b"G96 S200 D2500"[..], b"G96 D2500 S200", b"D2500 S200 G96"] {
+        let mut interpreter = Interpreter::default();
+        try_interpret_block(&mut interpreter, input)?;
+        assert_eq!(
+            interpreter.get_model_state().spindle_mode,
+            SpindleMode::ConstantSurfaceSpeed {
+                max_rpm: Some(2500.0)
+            }
+        );
+        assert_eq!(interpreter.get_model_state().spindle_speed, 200.0);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_surface_speed_at_center() -> Result<(), Box<dyn Error>> {
+    // D bounds the speed on the spindle axis, and with it the feedrate per revolution
+    let mut interpreter = Interpreter::default();
+    try_interpret_block(&mut interpreter, b"G96 D2000 S100")?;
+    assert_rpm(&interpreter, 2000.0);
+    try_interpret_block(&mut interpreter, b"G95 F0.1")?;
+    let feedrate = interpreter.get_model_state().feedrate.0;
+    assert!((feedrate - 0.1 * 2000.0 / 60.0).abs() < 1e-6);
+
+    // without D the speed is unbounded at X0
+    let mut interpreter = Interpreter::default();
+    try_interpret(&mut interpreter, b"G96")?;
+    assert_eq!(
+        interpret_error(&mut interpreter, b"S100"),
+        InterpretError::SurfaceSpeedAtCenter
+    );
+    let mut interpreter = Interpreter::default();
+    try_interpret_block(&mut interpreter, b"G0 X10")?;
+    try_interpret_block(&mut interpreter, b"G96 S100 F100")?;
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G1 X0"),
+        InterpretError::SurfaceSpeedAtCenter
+    );
+
+    // a stopped spindle has no speed to bound
+    try_interpret(&mut interpreter, b"S0")?;
+    assert_rpm(&interpreter, 0.0);
+    Ok(())
+}
+
+#[test]
+fn test_diameter_mode() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret(&mut interpreter, b"G7")?;
+    assert_eq!(
+        interpreter.get_model_state().diameter_mode,
+        DiameterMode::Diameter
+    );
+    let mv = single_move(try_interpret(&mut interpreter, b"G0 X20 Z5")?);
+    assert_position(&mv.to, [10.0, 0.0, 5.0]);
+
+    // incremental moves are diameter changes too
+    try_interpret_block(&mut interpreter, b"G91 G0 X-4")?;
+    assert_position(&interpreter.get_model_state().abs_position, [8.0, 0.0, 5.0]);
+
+    try_interpret_block(&mut interpreter, b"G90 G8 G0 X20")?;
+    assert_position(
+        &interpreter.get_model_state().abs_position,
+        [20.0, 0.0, 5.0],
+    );
+    Ok(())
+}
+
+#[test]
+fn test_spindle_errors() {
+    let mut interpreter = Interpreter::default();
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G96 D-1"),
+        InterpretError::NegativeSpindleSpeed(-1.0)
+    );
+}
@@ -0,0 +1,191 @@
+use super::{assert_position, interpret_error, moves, try_interpret, try_interpret_block};
+use crate::{
+    interpret::{
+        interpreter::InterpretError,
+        model_state::MmSec,
+        motion::{Move, MoveKind},
+        spline::SplineError,
+    },
+    Interpreter,
+};
+use alloc::{boxed::Box, vec::Vec};
+use core::error::Error;
+
+extern crate std;
+
+/// At X0 Y0 Z1, feeding at 10 mm/s
+fn interpreter() -> Result<Interpreter, Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret_block(&mut interpreter, b"G0 X0 Y0 Z1 F600")?;
+    Ok(interpreter)
+}
+
+/// Asserts the lines are feed moves from `start` keeping Z, and that every sampled
+/// point of the curve is within `tolerance` of them
+#[track_caller]
+fn assert_lines(moves: &[Move], start: [f64; 2], curve: impl Fn(f64) -> [f64; 2], tolerance: f64) {
+    assert!(moves.len() > 2, "{:?}", moves);
+    let mut points = Vec::from([start]);
+    for mv in moves {
+        assert_eq!(mv.kind, MoveKind::Linear);
+        assert_eq!(mv.feedrate, moves[0].feedrate);
+        assert_eq!(mv.to[2], 1.0);
+        points.push([mv.to[0], mv.to[1]]);
+    }
+    for step in 0..=200 {
+        let [x, y] = curve(step as f64 / 200.0);
+        let distance = points
+            .windows(2)
+            .map(|line| {
+                let ([x0, y0], [x1, y1]) = (line[0], line[1]);
+                let (dx, dy) = (x1 - x0, y1 - y0);
+                let along = (((x - x0) * dx + (y - y0) * dy) / (dx * dx + dy * dy)).clamp(0.0, 1.0);
+                let (ex, ey) = (x - x0 - along * dx, y - y0 - along * dy);
+                (ex * ex + ey * ey).sqrt()
+            })
+            .fold(f64::INFINITY, f64::min);
+        assert!(distance <= tolerance + 1e-9, "{x} {y} is {distance} off");
+    }
+}
+
+#[test]
+fn test_quadratic_spline() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter()?;
+    let moves = moves(try_interpret(&mut interpreter, b"G5.1 X10 Y0 I5 J5")?);
+    let curve = |t: f64| [10.0 * t, 10.0 * t * (1.0 - t)];
+    assert_lines(&moves, [0.0, 0.0], curve, 0.0127);
+    assert_position(&moves.last().unwrap().to, [10.0, 0.0, 1.0]);
+    assert_position(
+        &interpreter.get_model_state().abs_position,
+        [10.0, 0.0, 1.0],
+    );
+    assert_eq!(moves[0].feedrate, MmSec(10.0));
+
+    // a coarser tolerance makes fewer lines
+    interpreter.set_spline_tolerance(0.1);
+    let coarse = super::moves(try_interpret(&mut interpreter, b"G5.1 X0 Y0 I-5 J5")?);
+    assert!(coarse.len() < moves.len());
+    let curve = |t: f64| [10.0 - 10.0 * t, 10.0 * t * (1.0 - t)];
+    assert_lines(&coarse, [10.0, 0.0], curve, 0.1);
+    Ok(())
+}
+
+#[test]
+fn test_cubic_spline() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter()?;
+    let moves = moves(try_interpret(&mut interpreter, b"G5 X10 Y0 I0 J5 P0 Q5")?);
+    let cubic = |t: f64, [y0, y1, y2, y3]: [f64; 4]| {
+        let s = 1.0 - t;
+        s * s * s * y0 + 3.0 * s * s * t * y1 + 3.0 * s * t * t * y2 + t * t * t * y3
+    };
+    let curve = |t: f64| {
+        [
+            cubic(t, [0.0, 0.0, 10.0, 10.0]),
+            cubic(t, [0.0, 5.0, 5.0, 0.0]),
+        ]
+    };
+    assert_lines(&moves, [0.0, 0.0], curve, 0.0127);
+
+    // without I and J the curve continues smoothly from the last one, in inches
+    try_interpret(&mut interpreter, b"G20")?;
+    let moves = super::moves(try_interpret(
+        &mut interpreter,
+        b"G5 X0.7874 Y0 P0 Q-0.19685",
+    )?);
+    let curve = |t: f64| {
+        [
+            cubic(t, [10.0, 10.0, 20.0, 20.0]),
+            cubic(t, [0.0, -5.0, -5.0, 0.0]),
+        ]
+    };
+    assert_lines(&moves, [10.0, 0.0], curve, 0.0127);
+
+    // not after another motion
+    try_interpret(&mut interpreter, b"G1 X0")?;
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G5 X1 P0 Q1"),
+        InterpretError::MissingWord('I')
+    );
+    Ok(())
+}
+
+#[test]
+fn test_nurbs() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret_block(&mut interpreter, b"G0 X10 Y0 Z1 F600")?;
+    // a quarter circle around X0 Y0, the control points are no moves
+    let weight = core::f64::consts::FRAC_1_SQRT_2;
+    let input = std::format!("G5.2 X10 Y10 P{weight:.9} L3");
+    assert_eq!(
+        moves(try_interpret(&mut interpreter, input.as_bytes())?),
+        []
+    );
+    assert_eq!(moves(try_interpret(&mut interpreter, b"X0 Y10 P1")?), []);
+    assert_position(
+        &interpreter.get_model_state().abs_position,
+        [0.0, 10.0, 1.0],
+    );
+    let moves = moves(try_interpret(&mut interpreter, b"G5.3")?);
+    assert!(moves.len() > 2);
+    for mv in &moves {
+        let radius = (mv.to[0] * mv.to[0] + mv.to[1] * mv.to[1]).sqrt();
+        assert!((radius - 10.0).abs() < 1e-5, "{:?}", mv);
+    }
+    let curve = |t: f64| {
+        let angle = t * core::f64::consts::FRAC_PI_2;
+        [10.0 * angle.cos(), 10.0 * angle.sin()]
+    };
+    assert_lines(&moves, [10.0, 0.0], curve, 0.0127);
+
+    // unweighted, control points as bare axis words
+    try_interpret(&mut interpreter, b"G5.2 X0 Y0")?;
+    try_interpret(&mut interpreter, b"X10 Y0")?;
+    let moves = super::moves(try_interpret(&mut interpreter, b"G5.3")?);
+    let curve = |t: f64| [10.0 * t * t, 10.0 * (1.0 - t) * (1.0 - t)];
+    assert_lines(&moves, [0.0, 10.0], curve, 0.0127);
+    Ok(())
+}
+
+#[test]
+fn test_spline_errors() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter()?;
+    for (input, error) in [
+        (&b"G5.1 X1 I1"[..], InterpretError::MissingWord('J')),
+        (b"G5.1 X1 I0 J0", SplineError::ZeroOffsets.into()),
+        (b"G5 X1 I1 J1 P1", InterpretError::MissingWord('Q')),
+        (b"G5 X1 I1 P1 Q1", InterpretError::MissingWord('J')),
+        (b"G5.2 X1 L1", SplineError::InvalidOrder(1.0).into()),
+        (b"G5.2 X1 L2.5", SplineError::InvalidOrder(2.5).into()),
+        (b"G5.3", SplineError::NoNurbs.into()),
+        (b"X1 Y1 P1", SplineError::NoNurbs.into()),
+    ] {
+        assert_eq!(interpret_error(&mut interpreter, input), error);
+    }
+
+    try_interpret(&mut interpreter, b"G5.2 X1 Y1")?;
+    assert_eq!(
+        interpret_error(&mut interpreter, b"X2 Y0 P0"),
+        SplineError::InvalidWeight(0.0).into()
+    );
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G1 X0"),
+        SplineError::NurbsNotEnded.into()
+    );
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G5.3"),
+        SplineError::TooFewPoints.into()
+    );
+
+    try_interpret(&mut interpreter, b"G18")?;
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G5.1 X1 I1 J1"),
+        SplineError::UnsupportedPlane.into()
+    );
+    try_interpret(&mut interpreter, b"G17")?;
+    try_interpret_block(&mut interpreter, b"G41.1 D1")?;
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G5.1 X1 I1 J1"),
+        InterpretError::CutterComp(crate::CompError::NotAllowed)
+    );
+    Ok(())
+}
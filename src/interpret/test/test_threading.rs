@@ -0,0 +1,211 @@
+use super::{assert_position, interpret_error, try_interpret, try_interpret_block};
+use crate::{
+    interpret::{
+        interpreter::{InterpretError, InterpretValue},
+        motion::{MotionEvent, MoveKind},
+        threading::ThreadError,
+    },
+    Interpreter,
+};
+use alloc::{boxed::Box, vec::Vec};
+use core::error::Error;
+
+extern crate std;
+
+#[derive(Debug)]
+enum Step {
+    Rapid([f64; 3]),
+    Synced(f64, [f64; 3]),
+    Tap(f64, [f64; 3]),
+}
+use Step::*;
+
+#[track_caller]
+fn assert_steps(value: InterpretValue, expected: &[Step]) {
+    let InterpretValue::Motion(events) = value else {
+        panic!("expected motion, got {:?}", value);
+    };
+    assert_eq!(
+        events.len(),
+        expected.len(),
+        "{:?} != {:?}",
+        events,
+        expected
+    );
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-4;
+    for (event, step) in events.iter().zip(expected) {
+        let MotionEvent::Move(mv) = event else {
+            panic!("{:?} != {:?}", events, expected);
+        };
+        let (pitch, to) = match (&mv.kind, step) {
+            (MoveKind::Rapid, Rapid(to)) => (None, to),
+            (MoveKind::Synced { pitch }, Synced(expected, to))
+            | (MoveKind::RigidTap { pitch }, Tap(expected, to)) => (Some((*pitch, *expected)), to),
+            _ => panic!("{:?} != {:?}", events, expected),
+        };
+        let matches = pitch.is_none_or(|(pitch, expected)| close(pitch, expected))
+            && (0..3).all(|idx| close(mv.to[idx], to[idx]));
+        assert!(matches, "{:?} != {:?}", events, expected);
+    }
+}
+
+/// At X`x` Y0 Z`z`
+fn interpreter_at(x: f64, z: f64) -> Interpreter {
+    let mut interpreter = Interpreter::default();
+    let input = std::format!("G0 X{x} Y0 Z{z}");
+    try_interpret(&mut interpreter, input.as_bytes()).unwrap();
+    interpreter
+}
+
+#[test]
+fn test_g33() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_at(1.0, 0.0);
+    assert_steps(
+        try_interpret(&mut interpreter, b"G33 Z-10 K1.25")?,
+        &[Synced(1.25, [1.0, 0.0, -10.0])],
+    );
+    assert_position(
+        &interpreter.get_model_state().abs_position,
+        [1.0, 0.0, -10.0],
+    );
+
+    // tapered, with the pitch in inches
+    try_interpret(&mut interpreter, b"G20")?;
+    assert_steps(
+        try_interpret(&mut interpreter, b"G33 X0.1 Z-0.5 K0.05")?,
+        &[Synced(1.27, [2.54, 0.0, -12.7])],
+    );
+    Ok(())
+}
+
+#[test]
+fn test_rigid_tap() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_at(0.0, 2.0);
+    assert_steps(
+        try_interpret(&mut interpreter, b"G33.1 Z-10 K1")?,
+        &[Tap(1.0, [0.0, 0.0, -10.0]), Tap(1.0, [0.0, 0.0, 2.0])],
+    );
+    // back where it started
+    assert_position(&interpreter.get_model_state().abs_position, [0.0, 0.0, 2.0]);
+    Ok(())
+}
+
+#[test]
+fn test_g76() -> Result<(), Box<dyn Error>> {
+    // external thread, the peak 0.5 below the drive line at X10, in four passes of
+    // constant depth and a spring pass
+    let mut interpreter = interpreter_at(10.0, 2.0);
+    let mut expected = Vec::new();
+    for (idx, depth) in [0.25, 0.5, 0.75, 1.0, 1.0].into_iter().enumerate() {
+        if idx > 0 {
+            expected.push(Rapid([10.0, 0.0, 2.0]));
+        }
+        expected.push(Rapid([9.5 - depth, 0.0, 2.0]));
+        expected.push(Synced(1.5, [9.5 - depth, 0.0, -20.0]));
+        expected.push(Rapid([10.0, 0.0, -20.0]));
+    }
+    expected.push(Rapid([10.0, 0.0, 2.0]));
+    assert_steps(
+        try_interpret(&mut interpreter, b"G76 P1.5 Z-20 I-0.5 J0.25 K1 H1")?,
+        &expected,
+    );
+    assert_position(
+        &interpreter.get_model_state().abs_position,
+        [10.0, 0.0, 2.0],
+    );
+    Ok(())
+}
+
+#[test]
+fn test_g76_degression_compound() -> Result<(), Box<dyn Error>> {
+    // internal thread, constant area passes, each starting further along -Z by its
+    // depth at 45 degrees
+    let mut interpreter = interpreter_at(5.0, 0.0);
+    let mut expected = Vec::new();
+    let depths = [0.2, 0.2 * 2f64.sqrt(), 0.2 * 3f64.sqrt(), 0.4];
+    for depth in depths {
+        expected.push(Rapid([5.0, 0.0, -depth]));
+        expected.push(Rapid([5.5 + depth, 0.0, -depth]));
+        expected.push(Synced(1.0, [5.5 + depth, 0.0, -10.0 - depth]));
+        expected.push(Rapid([5.0, 0.0, -10.0 - depth]));
+    }
+    expected.push(Rapid([5.0, 0.0, 0.0]));
+    assert_steps(
+        try_interpret(&mut interpreter, b"G76 P1 Z-10 I0.5 J0.2 K0.4 R2 Q45")?,
+        &expected,
+    );
+    Ok(())
+}
+
+#[test]
+fn test_g76_tapers() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_at(10.0, 0.0);
+    // the tapers start and end at the peak, their pitch is along the slant
+    let taper_pitch = 5f64.sqrt();
+    assert_steps(
+        try_interpret(&mut interpreter, b"G76 P2 Z-20 I-1 J1 K1 E2 L3")?,
+        &[
+            Synced(taper_pitch, [9.0, 0.0, 0.0]),
+            Synced(taper_pitch, [8.0, 0.0, -2.0]),
+            Synced(2.0, [8.0, 0.0, -18.0]),
+            Synced(taper_pitch, [9.0, 0.0, -20.0]),
+            Rapid([10.0, 0.0, -20.0]),
+            Rapid([10.0, 0.0, 0.0]),
+        ],
+    );
+
+    // exit taper only
+    assert_steps(
+        try_interpret(&mut interpreter, b"G76 P2 Z-20 I-1 J1 K1 E2 L2")?,
+        &[
+            Rapid([8.0, 0.0, 0.0]),
+            Synced(2.0, [8.0, 0.0, -18.0]),
+            Synced(taper_pitch, [9.0, 0.0, -20.0]),
+            Rapid([10.0, 0.0, -20.0]),
+            Rapid([10.0, 0.0, 0.0]),
+        ],
+    );
+    Ok(())
+}
+
+#[test]
+fn test_threading_errors() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_at(10.0, 0.0);
+    for (input, error) in [
+        (&b"G33 Z-10"[..], InterpretError::MissingWord('K')),
+        (b"G33 Z-10 K0", ThreadError::InvalidPitch(0.0).into()),
+        (b"G33.1 Z-10 K-1", ThreadError::InvalidPitch(-1.0).into()),
+        (b"G76 Z-10 I-1 J0.1 K1", InterpretError::MissingWord('P')),
+        (b"G76 P1 I-1 J0.1 K1", InterpretError::MissingWord('Z')),
+        (b"G76 P1 Z-10 I0 J0.1 K1", ThreadError::ZeroPeak.into()),
+        (
+            b"G76 P1 Z-10 I-1 J0 K1",
+            ThreadError::InvalidFirstCut(0.0).into(),
+        ),
+        (
+            b"G76 P1 Z-10 I-1 J0.5 K0.4",
+            ThreadError::DepthBelowFirstCut.into(),
+        ),
+        (
+            b"G76 P1 Z-10 I-1 J0.1 K1 R0.5",
+            ThreadError::InvalidDegression(0.5).into(),
+        ),
+        (
+            b"G76 P1 Z-10 I-1 J0.1 K1 H1.5",
+            ThreadError::InvalidSpringPasses(1.5).into(),
+        ),
+        (
+            b"G76 P1 Z-10 I-1 J0.1 K1 L4",
+            ThreadError::InvalidTaper(4.0).into(),
+        ),
+    ] {
+        assert_eq!(interpret_error(&mut interpreter, input), error);
+    }
+
+    try_interpret_block(&mut interpreter, b"G41.1 D1")?;
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G76 P1 Z-10 I-1 J0.1 K1"),
+        InterpretError::CutterComp(crate::CompError::NotAllowed)
+    );
+    Ok(())
+}
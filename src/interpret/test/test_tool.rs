@@ -0,0 +1,184 @@
+use super::{assert_position, interpret_error, single_move, try_interpret, try_interpret_block};
+use crate::{
+    interpret::{
+        interpreter::InterpretError,
+        model_state::Position,
+        tool_table::{TblError, Tool, ToolTable},
+    },
+    Interpreter,
+};
+use alloc::boxed::Box;
+use core::error::Error;
+
+extern crate std;
+
+const TBL: &str = "\
+T1 P1 Z10.5 D6 ;end mill
+t2 p5 x0.5 z-2 d0.8 i95 j155 q3
+
+; probe
+T99 P10 Z120 D2
+";
+
+fn interpreter_with_tools() -> Interpreter {
+    let mut interpreter = Interpreter::default();
+    *interpreter.tool_table_mut() = ToolTable::from_tbl(TBL).unwrap();
+    try_interpret(&mut interpreter, b"F600").unwrap();
+    interpreter
+}
+
+#[test]
+fn test_tool_table_from_tbl() {
+    let table = ToolTable::from_tbl(TBL).unwrap();
+    assert_eq!(
+        table
+            .iter()
+            .map(|(number, _)| number)
+            .collect::<std::vec::Vec<_>>(),
+        [1, 2, 99]
+    );
+    assert_eq!(
+        table.get(2),
+        Some(&Tool {
+            pocket: 5,
+            offset: Position::new([0.5, 0.0, -2.0]),
+            diameter: 0.8,
+            front_angle: 95.0,
+            back_angle: 155.0,
+            orientation: 3,
+        })
+    );
+    assert_eq!(table.get(99).unwrap().diameter, 2.0);
+
+    assert_eq!(
+        ToolTable::from_tbl("T1 P1\nP2 Z1"),
+        Err(TblError::MissingTool(2))
+    );
+    assert_eq!(
+        ToolTable::from_tbl("T1 P1 Zabc"),
+        Err(TblError::InvalidWord(1))
+    );
+    assert_eq!(ToolTable::from_tbl("T1.5"), Err(TblError::InvalidWord(1)));
+    assert_eq!(
+        ToolTable::from_tbl("T1 Q9\nT2 Q10"),
+        Err(TblError::InvalidOrientation(2))
+    );
+    assert_eq!(
+        ToolTable::from_tbl("T1 Q300"),
+        Err(TblError::InvalidOrientation(1))
+    );
+}
+
+#[test]
+fn test_tool_change() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_with_tools();
+    assert_eq!(interpreter.get_model_state().tool, 0);
+
+    // T only selects
+    try_interpret(&mut interpreter, b"T2")?;
+    assert_eq!(interpreter.get_model_state().selected_tool, 2);
+    assert_eq!(interpreter.get_model_state().tool, 0);
+
+    try_interpret(&mut interpreter, b"M6")?;
+    assert_eq!(interpreter.get_model_state().tool, 2);
+    assert_eq!(interpreter.get_numbered_param(5400), Some(2.0));
+    assert_eq!(interpreter.get_numbered_param(5403), Some(-2.0));
+    assert_eq!(interpreter.get_numbered_param(5410), Some(0.8));
+    assert_eq!(interpreter.get_numbered_param(5413), Some(3.0));
+
+    try_interpret_block(&mut interpreter, b"T1 M6")?;
+    assert_eq!(interpreter.get_model_state().tool, 1);
+
+    assert_eq!(
+        interpret_error(&mut interpreter, b"T7"),
+        InterpretError::UnknownTool(7)
+    );
+    assert_eq!(
+        interpret_error(&mut interpreter, b"T-1"),
+        InterpretError::InvalidTool(-1.0)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_tool_length_offset() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_with_tools();
+    try_interpret_block(&mut interpreter, b"T1 M6")?;
+
+    // the offset applies from the next move, without moving
+    try_interpret(&mut interpreter, b"G43")?;
+    let mv = single_move(try_interpret(&mut interpreter, b"G0 Z5")?);
+    assert_position(&mv.to, [0.0, 0.0, 15.5]);
+
+    // H selects another tool, the offset holds every axis of the table
+    try_interpret(&mut interpreter, b"G43 H2")?;
+    let mv = single_move(try_interpret(&mut interpreter, b"G0 X1 Z5")?);
+    assert_position(&mv.to, [1.5, 0.0, 3.0]);
+
+    // dynamic, in the selected unit
+    try_interpret_block(&mut interpreter, b"G20 G43.1 Z1")?;
+    assert_position(&interpreter.get_model_state().tool_offset, [0.0, 0.0, 25.4]);
+    try_interpret(&mut interpreter, b"G21")?;
+
+    // additive, from a tool and from the axis words
+    try_interpret(&mut interpreter, b"G43.2 H1 Z0.5")?;
+    assert_position(&interpreter.get_model_state().tool_offset, [0.0, 0.0, 36.4]);
+
+    try_interpret(&mut interpreter, b"G49")?;
+    let mv = single_move(try_interpret(&mut interpreter, b"G0 Z5")?);
+    assert_position(&mv.to, [1.5, 0.0, 5.0]);
+
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G43 H8"),
+        InterpretError::UnknownTool(8)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_tool_set_offsets() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_with_tools();
+
+    // L1 writes the table
+    try_interpret(&mut interpreter, b"G10 L1 P2 Z-3 R1.5 Q4")?;
+    let tool = *interpreter.tool_table().get(2).unwrap();
+    assert_position(&tool.offset, [0.5, 0.0, -3.0]);
+    assert_eq!(tool.diameter, 3.0);
+    assert_eq!(tool.orientation, 4);
+
+    // L10: the tool touches Z0 of G54 at machine Z 40
+    try_interpret(&mut interpreter, b"G10 L2 P1 Z20")?;
+    try_interpret(&mut interpreter, b"G54")?;
+    try_interpret(&mut interpreter, b"G0 Z20")?;
+    try_interpret(&mut interpreter, b"G10 L10 P1 Z0")?;
+    assert_position(
+        &interpreter.tool_table().get(1).unwrap().offset,
+        [0.0, 0.0, 20.0],
+    );
+    // ... and now reads Z0 with the offset
+    try_interpret_block(&mut interpreter, b"T1 M6")?;
+    try_interpret(&mut interpreter, b"G43")?;
+    try_interpret(&mut interpreter, b"G0 Z0")?;
+    assert_position(
+        &interpreter.get_model_state().abs_position,
+        [0.0, 0.0, 40.0],
+    );
+
+    // L11 is relative to G59.3
+    try_interpret(&mut interpreter, b"G10 L2 P9 Z5")?;
+    try_interpret(&mut interpreter, b"G10 L11 P99 Z1")?;
+    assert_position(
+        &interpreter.tool_table().get(99).unwrap().offset,
+        [0.0, 0.0, 34.0],
+    );
+
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G10 L1 P7 Z1"),
+        InterpretError::UnknownTool(7)
+    );
+    assert_eq!(
+        interpret_error(&mut interpreter, b"G10 L1 P2 Q10"),
+        InterpretError::InvalidQ(10.0)
+    );
+    Ok(())
+}
@@ -0,0 +1,258 @@
+use super::{assert_position, single_move, try_interpret, try_interpret_block};
+use crate::{
+    interpret::{model_state::Workspace, motion::MoveKind},
+    Axis, Interpreter,
+};
+use alloc::boxed::Box;
+use core::error::Error;
+
+extern crate std;
+
+#[test]
+fn test_select_workspace() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    assert_eq!(interpreter.get_model_state().workspace, Workspace::Machine);
+
+    for (input, workspace, number) in [
+        (&b"G54"[..], Workspace::G54, 1.0),
+        (b"G55", Workspace::G55, 2.0),
+        (b"G56", Workspace::G56, 3.0),
+        (b"G57", Workspace::G57, 4.0),
+        (b"G58", Workspace::G58, 5.0),
+        (b"G59", Workspace::G59, 6.0),
+        (b"G59.1", Workspace::G59_1, 7.0),
+        (b"G59.2", Workspace::G59_2, 8.0),
+        (b"G59.3", Workspace::G59_3, 9.0),
+    ] {
+        try_interpret(&mut interpreter, input)?;
+        assert_eq!(interpreter.get_model_state().workspace, workspace);
+        assert_eq!(interpreter.get_numbered_param(5220), Some(number));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_work_offset_from_params() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    // G55 is X #5241, Y #5242, Z #5243
+    try_interpret_block(&mut interpreter, b"#5241=10 #5243=3 F600")?;
+    try_interpret(&mut interpreter, b"G55")?;
+
+    let mv = single_move(try_interpret(&mut interpreter, b"G2 X0 Y0 I5")?);
+    let MoveKind::Arc(arc) = mv.kind else {
+        panic!("expected an arc, got {:?}", mv.kind);
+    };
+    // program X0 is machine X10, Z has no word and stays at machine 0
+    assert_position(&mv.to, [10.0, 0.0, 0.0]);
+    assert_position(&arc.center, [5.0, 0.0, 0.0]);
+
+    // the offset of G54 is unset, program and machine coordinates match
+    try_interpret(&mut interpreter, b"G54")?;
+    let mv = single_move(try_interpret(&mut interpreter, b"G3 X0 Y0 Z1 I-5")?);
+    assert_position(&mv.to, [0.0, 0.0, 1.0]);
+    Ok(())
+}
+
+#[test]
+fn test_work_offset_last_system() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    // G59.3 is X #5381 to the last axis at #5389
+    try_interpret_block(&mut interpreter, b"#5382=-4 F600 G59.3")?;
+    let mv = single_move(try_interpret(&mut interpreter, b"G2 X0 Y4 J2")?);
+    assert_position(&mv.to, [0.0, 0.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn test_work_offset_inches() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    // offsets are stored in mm whatever the selected unit
+    interpreter.set_work_offset(Workspace::G54, Axis::X, 25.4);
+    try_interpret_block(&mut interpreter, b"G20 G54 F10")?;
+    assert_eq!(interpreter.get_numbered_param(5221), Some(25.4));
+
+    let mv = single_move(try_interpret(&mut interpreter, b"G2 X0 Y0 I0.5")?);
+    let MoveKind::Arc(arc) = mv.kind else {
+        panic!("expected an arc, got {:?}", mv.kind);
+    };
+    assert_position(&mv.to, [25.4, 0.0, 0.0]);
+    assert_position(&arc.center, [12.7, 0.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn test_machine_workspace_has_no_offset() {
+    let mut interpreter = Interpreter::default();
+    interpreter.set_work_offset(Workspace::Machine, Axis::X, 10.0);
+    assert_eq!(interpreter.get_numbered_param(5220), None);
+    assert_eq!(interpreter.get_numbered_param(5221), None);
+}
+
+#[test]
+fn test_g10_l2() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret(&mut interpreter, b"G10 L2 P2 X10 Z-3")?;
+    assert_eq!(interpreter.get_numbered_param(5241), Some(10.0));
+    assert_eq!(interpreter.get_numbered_param(5242), None);
+    assert_eq!(interpreter.get_numbered_param(5243), Some(-3.0));
+    // the active coordinate system is not changed
+    assert_eq!(interpreter.get_model_state().workspace, Workspace::Machine);
+
+    // axes without a word keep their offset, values are converted to mm
+    try_interpret_block(&mut interpreter, b"G20 G10 L2 P2 X1")?;
+    assert_eq!(interpreter.get_numbered_param(5241), Some(25.4));
+    assert_eq!(interpreter.get_numbered_param(5243), Some(-3.0));
+
+    // later reads of the parameters see the new offsets
+    try_interpret_block(&mut interpreter, b"G21 G10 L2 P9 Y[#5241 * 2]")?;
+    assert_eq!(interpreter.get_numbered_param(5382), Some(50.8));
+    Ok(())
+}
+
+#[test]
+fn test_g10_l2_p0_active_system() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret_block(&mut interpreter, b"G56 G10 L2 P0 X5 F600")?;
+    assert_eq!(interpreter.get_numbered_param(5261), Some(5.0));
+
+    // G10 runs before the motion of its block
+    let mv =
+        single_move(try_interpret_block(&mut interpreter, b"G2 X0 Y0 I5 G10 L2 P3 X10")?.remove(1));
+    assert_position(&mv.to, [10.0, 0.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn test_g10_l20() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = Interpreter::default();
+    try_interpret_block(&mut interpreter, b"G54 F600")?;
+    try_interpret(&mut interpreter, b"G2 X10 Y0 I5")?;
+
+    // make the current position X1 Y2 in G54
+    try_interpret(&mut interpreter, b"G10 L20 P1 X1 Y2")?;
+    assert_eq!(interpreter.get_numbered_param(5221), Some(9.0));
+    assert_eq!(interpreter.get_numbered_param(5222), Some(-2.0));
+
+    // moving to X1 Y2 stays in place
+    let mv = single_move(try_interpret(&mut interpreter, b"G2 X1 Y2 I1 P2")?);
+    assert_position(&mv.to, [10.0, 0.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn test_g10_errors() {
+    use crate::GcodeParser as _;
+    use crate::{gcode::Command, interpret::interpreter::InterpretError, parser::Input};
+
+    let mut interpreter = Interpreter::default();
+    for (input, expected) in [
+        (&b"G10 P1 X0"[..], InterpretError::MissingWord('L')),
+        (b"G10 L2 X0", InterpretError::MissingWord('P')),
+        (b"G10 L3 P1 X0", InterpretError::InvalidL(3.0)),
+        (b"G10 L2.5 P1 X0", InterpretError::InvalidL(2.5)),
+        (b"G10 L2 P10 X0", InterpretError::InvalidP(10.0)),
+        (b"G10 L2 P1.5 X0", InterpretError::InvalidP(1.5)),
+        (b"G10 L2 P-1 X0", InterpretError::InvalidP(-1.0)),
+        // P0 with machine coordinates active
+        (b"G10 L2 P0 X0", InterpretError::InvalidP(0.0)),
+    ] {
+        let command = Command::parse(Input::new(input, &interpreter)).unwrap().1;
+        assert_eq!(interpreter.interpret(command), Err(expected));
+    }
+}
+
+/// interpreter at machine X10 Y0, in machine coordinates
+fn interpreter_at_x10() -> Interpreter {
+    let mut interpreter = Interpreter::default();
+    try_interpret(&mut interpreter, b"F600").unwrap();
+    try_interpret(&mut interpreter, b"G2 X10 Y0 I5").unwrap();
+    interpreter
+}
+
+#[test]
+fn test_g92() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_at_x10();
+    try_interpret_block(&mut interpreter, b"G54 #5221=4")?;
+    assert_eq!(interpreter.get_numbered_param(5210), None);
+
+    // current position becomes X1, Z has no word and keeps its offset
+    try_interpret(&mut interpreter, b"G92 X1")?;
+    let state = interpreter.get_model_state();
+    assert_position(&state.axis_offsets, [5.0, 0.0, 0.0]);
+    // the work offset is kept separately
+    assert_eq!(interpreter.get_numbered_param(5221), Some(4.0));
+    assert_eq!(interpreter.get_numbered_param(5210), Some(1.0));
+    assert_eq!(interpreter.get_numbered_param(5211), Some(5.0));
+    assert_eq!(interpreter.get_numbered_param(5212), Some(0.0));
+
+    // program X1 is machine X10
+    let mv = single_move(try_interpret(&mut interpreter, b"G2 X1 Y0 I-1 P2")?);
+    assert_position(&mv.to, [10.0, 0.0, 0.0]);
+
+    // G92 applies to every coordinate system
+    try_interpret_block(&mut interpreter, b"G55 #5241=2")?;
+    let mv = single_move(try_interpret(&mut interpreter, b"G2 X3 Y0 I-1 P2")?);
+    assert_position(&mv.to, [10.0, 0.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn test_g92_inches() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_at_x10();
+    try_interpret_block(&mut interpreter, b"G20 G92 X0.1")?;
+    assert_position(
+        &interpreter.get_model_state().axis_offsets,
+        [7.46, 0.0, 0.0],
+    );
+    Ok(())
+}
+
+#[test]
+fn test_g92_1_g92_2_g92_3() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_at_x10();
+    try_interpret(&mut interpreter, b"G92 X0 Y-2")?;
+
+    // G92.2 keeps the parameters, G92.3 restores from them
+    try_interpret(&mut interpreter, b"G92.2")?;
+    assert_position(&interpreter.get_model_state().axis_offsets, [0.0; 3]);
+    assert_eq!(interpreter.get_numbered_param(5210), Some(0.0));
+    assert_eq!(interpreter.get_numbered_param(5211), Some(10.0));
+    assert_eq!(interpreter.get_numbered_param(5212), Some(2.0));
+
+    try_interpret(&mut interpreter, b"G92.3")?;
+    assert_position(
+        &interpreter.get_model_state().axis_offsets,
+        [10.0, 2.0, 0.0],
+    );
+    assert_eq!(interpreter.get_numbered_param(5210), Some(1.0));
+
+    // G92.1 clears the parameters too
+    try_interpret(&mut interpreter, b"G92.1")?;
+    assert_position(&interpreter.get_model_state().axis_offsets, [0.0; 3]);
+    assert_eq!(interpreter.get_numbered_param(5210), Some(0.0));
+    assert_eq!(interpreter.get_numbered_param(5211), Some(0.0));
+    try_interpret(&mut interpreter, b"G92.3")?;
+    assert_position(&interpreter.get_model_state().axis_offsets, [0.0; 3]);
+
+    // offsets set through the parameters are restored by G92.3
+    try_interpret_block(&mut interpreter, b"#5213=-1 G92.3")?;
+    assert_position(
+        &interpreter.get_model_state().axis_offsets,
+        [0.0, 0.0, -1.0],
+    );
+    Ok(())
+}
+
+#[test]
+fn test_g10_l20_with_g92() -> Result<(), Box<dyn Error>> {
+    let mut interpreter = interpreter_at_x10();
+    try_interpret(&mut interpreter, b"G54")?;
+    try_interpret(&mut interpreter, b"G92 X7")?;
+
+    // the current position is X0 in G54 with the G92 offset of 3 still applied
+    try_interpret(&mut interpreter, b"G10 L20 P1 X0")?;
+    assert_eq!(interpreter.get_numbered_param(5221), Some(7.0));
+    let mv = single_move(try_interpret(&mut interpreter, b"G2 X0 Y0 I-1 P2")?);
+    assert_position(&mv.to, [10.0, 0.0, 0.0]);
+    Ok(())
+}
@@ -0,0 +1,165 @@
+//! The `G76` threading cycle, expanded into spindle synchronized passes following
+//! LinuxCNC's `convert_threading_cycle`. All lengths are in mm, in machine coordinates.
+
+use super::{
+    math::{powf, sqrt, tan},
+    model_state::{MmSec, PathMode, Position},
+    motion::{MotionEvent, Move, MoveKind},
+};
+use crate::{gcode::Axis, NUM_AXES};
+use alloc::vec::Vec;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ThreadError {
+    /// `P` of a `G76` or `K` of a `G33` / `G33.1` which is not positive
+    InvalidPitch(f32),
+    /// `I` of zero, the thread peak cannot be on the drive line
+    ZeroPeak,
+    /// `J` is not positive
+    InvalidFirstCut(f32),
+    /// `K` is less than `J`
+    DepthBelowFirstCut,
+    /// `R` is below 1
+    InvalidDegression(f32),
+    /// `H` is not a whole number of passes
+    InvalidSpringPasses(f32),
+    /// `L` is not 0, 1, 2 or 3
+    InvalidTaper(f32),
+}
+
+/// A `G76` thread, cut along Z from the start position, which is on the drive line
+#[derive(Debug, PartialEq, Clone)]
+pub struct Thread {
+    pub end_z: f64,
+    pub pitch: f64,
+    /// `I`, X offset of the thread peak from the drive line
+    pub peak: f64,
+    /// `J`, depth of the first pass beyond the peak
+    pub first_cut: f64,
+    /// `K`, depth of the last passes beyond the peak
+    pub depth: f64,
+    /// `R`, pass `n` is `first_cut * n^(1 / degression)` deep
+    pub degression: f64,
+    /// `Q`, in radians, each pass starts further along Z by its depth times the tangent
+    pub compound_angle: f64,
+    /// `H`, passes repeated at full depth
+    pub spring_passes: u32,
+    /// `E`, length along Z of the tapers
+    pub taper: f64,
+    pub entry_taper: bool,
+    pub exit_taper: bool,
+    pub path_mode: PathMode,
+}
+
+impl Thread {
+    /// Depths of the passes beyond the thread peak, ending with the full depth passes
+    pub fn pass_depths(&self) -> Vec<f64> {
+        let mut depths = Vec::new();
+        let mut pass = 1;
+        let mut depth = self.first_cut;
+        while depth < self.depth {
+            depths.push(depth);
+            pass += 1;
+            depth = self.first_cut * powf(pass as f64, 1.0 / self.degression);
+        }
+        for _ in 0..=self.spring_passes {
+            depths.push(self.depth);
+        }
+        depths
+    }
+
+    /// Events for every pass from `start`, ending back at `start`
+    pub fn expand(&self, start: Position<NUM_AXES>) -> Vec<MotionEvent> {
+        let mut pass = Pass {
+            thread: self,
+            drive_x: start[Axis::X.to_idx()],
+            direction: if self.end_z < start[Axis::Z.to_idx()] {
+                -1.0
+            } else {
+                1.0
+            },
+            // with a taper the pitch is along the slanted move
+            taper_pitch: if self.taper > 0.0 {
+                self.pitch * sqrt(self.taper * self.taper + self.depth * self.depth) / self.taper
+            } else {
+                self.pitch
+            },
+            position: start,
+            events: Vec::new(),
+        };
+        for depth in self.pass_depths() {
+            pass.cut(start[Axis::Z.to_idx()], depth);
+        }
+        pass.move_to(MoveKind::Rapid, start);
+        pass.events
+    }
+}
+
+/// Events of the passes, skipping moves which go nowhere
+struct Pass<'a> {
+    thread: &'a Thread,
+    drive_x: f64,
+    /// +1 or -1, the direction of the cut along Z
+    direction: f64,
+    taper_pitch: f64,
+    position: Position<NUM_AXES>,
+    events: Vec<MotionEvent>,
+}
+
+impl Pass<'_> {
+    /// X of a cut `depth` beyond the thread peak, away from the drive line
+    fn x(&self, depth: f64) -> f64 {
+        self.drive_x + self.thread.peak + self.thread.peak.signum() * depth
+    }
+
+    /// back along the drive line, in, over to the end of the thread and out
+    fn cut(&mut self, start_z: f64, depth: f64) {
+        let thread = self.thread;
+        let shift = self.direction * depth * tan(thread.compound_angle);
+        let start_z = start_z + shift;
+        let end_z = thread.end_z + shift;
+        let synced = MoveKind::Synced {
+            pitch: thread.pitch,
+        };
+        let tapered = MoveKind::Synced {
+            pitch: self.taper_pitch,
+        };
+
+        self.move_xz(MoveKind::Rapid, self.drive_x, start_z);
+        if thread.taper > 0.0 && thread.entry_taper {
+            self.move_xz(tapered.clone(), self.x(depth - thread.depth), start_z);
+            let taper_end = start_z + self.direction * thread.taper;
+            self.move_xz(tapered.clone(), self.x(depth), taper_end);
+        } else {
+            self.move_xz(MoveKind::Rapid, self.x(depth), start_z);
+        }
+        if thread.taper > 0.0 && thread.exit_taper {
+            let taper_start = end_z - self.direction * thread.taper;
+            self.move_xz(synced, self.x(depth), taper_start);
+            self.move_xz(tapered, self.x(depth - thread.depth), end_z);
+        } else {
+            self.move_xz(synced, self.x(depth), end_z);
+        }
+        self.move_xz(MoveKind::Rapid, self.drive_x, end_z);
+    }
+
+    fn move_xz(&mut self, kind: MoveKind, x: f64, z: f64) {
+        let mut to = self.position;
+        to[Axis::X.to_idx()] = x;
+        to[Axis::Z.to_idx()] = z;
+        self.move_to(kind, to);
+    }
+
+    fn move_to(&mut self, kind: MoveKind, to: Position<NUM_AXES>) {
+        if to == self.position {
+            return;
+        }
+        self.events.push(MotionEvent::Move(Move {
+            kind,
+            to,
+            feedrate: MmSec::default(),
+            path_mode: self.thread.path_mode,
+        }));
+        self.position = to;
+    }
+}
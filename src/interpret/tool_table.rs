@@ -0,0 +1,110 @@
+//! Tool data, by tool number, loadable from a LinuxCNC `.tbl` file. All lengths are
+//! in mm.
+
+use super::model_state::Position;
+use crate::{gcode::Axis, NUM_AXES};
+use alloc::collections::BTreeMap;
+
+/// Data of a single tool
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct Tool {
+    /// pocket of the tool changer holding the tool
+    pub pocket: u32,
+    /// length offsets, applied by `G43`
+    pub offset: Position<NUM_AXES>,
+    pub diameter: f64,
+    /// lathe tool angles, in degrees
+    pub front_angle: f64,
+    pub back_angle: f64,
+    /// lathe tool orientation, 0 to 9
+    pub orientation: u8,
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ToolTable {
+    tools: BTreeMap<u32, Tool>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum TblError {
+    /// line without a `T` word, 1-based
+    MissingTool(usize),
+    /// word which is not a known letter followed by a number, 1-based line
+    InvalidWord(usize),
+    /// `Q` which is not a tool orientation, 0 to 9, 1-based line
+    InvalidOrientation(usize),
+}
+
+impl ToolTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read a LinuxCNC tool table, e.g. `T1 P1 Z0.511 D0.125 ;comment`, with lengths
+    /// in mm. Words for axes other than X, Y, Z are ignored.
+    pub fn from_tbl(text: &str) -> Result<Self, TblError> {
+        let mut table = Self::new();
+        for (idx, line) in text.lines().enumerate() {
+            let line_number = idx + 1;
+            let words = line.split(';').next().unwrap_or_default();
+            let mut number = None;
+            let mut tool = Tool::default();
+            let mut pocket = None;
+            for word in words.split_whitespace() {
+                let mut chars = word.chars();
+                let letter = chars.next().unwrap_or_default().to_ascii_uppercase();
+                let value: f64 = chars
+                    .as_str()
+                    .parse()
+                    .map_err(|_| TblError::InvalidWord(line_number))?;
+                let integer = || {
+                    if value < 0.0 || value as u32 as f64 != value {
+                        return Err(TblError::InvalidWord(line_number));
+                    }
+                    Ok(value as u32)
+                };
+                match letter {
+                    'T' => number = Some(integer()?),
+                    'P' => pocket = Some(integer()?),
+                    'D' => tool.diameter = value,
+                    'I' => tool.front_angle = value,
+                    'J' => tool.back_angle = value,
+                    'Q' => match integer()? {
+                        orientation @ 0..=9 => tool.orientation = orientation as u8,
+                        _ => return Err(TblError::InvalidOrientation(line_number)),
+                    },
+                    'X' | 'Y' | 'Z' => {
+                        if let Some(axis) = Axis::from_chr(letter) {
+                            tool.offset[axis.to_idx()] = value;
+                        }
+                    }
+                    'A' | 'B' | 'C' | 'U' | 'V' | 'W' => {}
+                    _ => return Err(TblError::InvalidWord(line_number)),
+                }
+            }
+            match number {
+                Some(number) => {
+                    tool.pocket = pocket.unwrap_or(number);
+                    table.set(number, tool);
+                }
+                None if words.trim().is_empty() => {}
+                None => return Err(TblError::MissingTool(line_number)),
+            }
+        }
+        Ok(table)
+    }
+
+    pub fn get(&self, tool: u32) -> Option<&Tool> {
+        self.tools.get(&tool)
+    }
+    pub fn get_mut(&mut self, tool: u32) -> Option<&mut Tool> {
+        self.tools.get_mut(&tool)
+    }
+    pub fn set(&mut self, tool: u32, data: Tool) {
+        self.tools.insert(tool, data);
+    }
+    /// (tool number, data) of every tool, by tool number
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &Tool)> + '_ {
+        self.tools.iter().map(|(number, tool)| (*number, tool))
+    }
+}
@@ -12,9 +12,42 @@ mod parse_error;
 mod parser;
 
 const NUM_AXES: usize = 3;
+pub use crate::gcode::Axis;
+pub use crate::gcode::Block;
 pub use crate::gcode::Command;
+pub use crate::interpret::ArcMove;
+pub use crate::interpret::CompError;
+pub use crate::interpret::ControlError;
+pub use crate::interpret::Coolant;
+pub use crate::interpret::CutterComp;
+pub use crate::interpret::CycleError;
+pub use crate::interpret::CycleKind;
+pub use crate::interpret::DiameterMode;
+pub use crate::interpret::DwellUnit;
+pub use crate::interpret::FeedMode;
 pub use crate::interpret::InterpretError;
+pub use crate::interpret::InterpretValue;
 pub use crate::interpret::Interpreter;
+pub use crate::interpret::MmSec;
+pub use crate::interpret::MotionEvent;
+pub use crate::interpret::MotionMode;
+pub use crate::interpret::Move;
+pub use crate::interpret::MoveKind;
+pub use crate::interpret::PathMode;
+pub use crate::interpret::Position;
+pub use crate::interpret::Probe;
+pub use crate::interpret::ProbeMove;
+pub use crate::interpret::ProbeTrigger;
+pub use crate::interpret::Program;
+pub use crate::interpret::RetractMode;
+pub use crate::interpret::SpindleDirection;
+pub use crate::interpret::SpindleMode;
+pub use crate::interpret::SplineError;
+pub use crate::interpret::TblError;
+pub use crate::interpret::ThreadError;
+pub use crate::interpret::Tool;
+pub use crate::interpret::ToolTable;
+pub use crate::interpret::Workspace;
 pub use crate::parser::GcodeParseError;
 pub use crate::parser::GcodeParser;
 pub use crate::parser::Input;
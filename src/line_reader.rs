@@ -65,7 +65,7 @@ mod tests {
                         lines.push(line.to_owned());
                     }
                     Ok(None) => break,
-                    Err(err) => panic!("{:?}", err),
+                    Err(err) => core::panic!("{:?}", err),
                 };
             }
             lines
@@ -1,11 +1,14 @@
-use crate::parser::nom_types::IParseResult;
-use crate::GcodeParseError;
+use crate::gcode::expression::Expression;
+use crate::parser::{fold_many0_result, nom_types::IParseResult};
+use crate::{GcodeParseError, GcodeParser as _};
 use nom::{
+    branch::alt,
     bytes::complete::tag,
     character::complete::digit1,
-    character::complete::space0,
-    combinator::{map_res, not},
-    sequence::{preceded, terminated},
+    character::complete::{satisfy, space0},
+    combinator::{consumed, map_res, not},
+    error::{Error, ErrorKind},
+    sequence::{pair, preceded, terminated},
     Parser,
 };
 
@@ -39,8 +42,38 @@ pub fn map_res_into_ok<'a, T: Into<R>, R>(
 pub fn number_code<'a>(
     number: &'static str,
 ) -> impl FnMut(Input<'a>) -> IParseResult<'a, Input<'a>> {
-    // exact number str followed by non-digit
-    terminated(tag(number), not(digit1))
+    // exact number str e.g. `38` or `38.2`, not followed by more digits or a
+    // decimal point, so that `38` does not match the start of `38.2` or `38.`
+    terminated(tag(number), not(alt((digit1, tag(".")))))
+}
+
+/// Words which follow a code, e.g. the `X`, `I` and `R` of a `G2`
+pub trait Words: Default {
+    /// letters of the words, in uppercase
+    const LETTERS: &'static str;
+    /// The word of the uppercase `letter`, `None` if it is not a word of `Self`
+    fn word(&mut self, letter: char) -> Option<&mut Option<Expression>>;
+}
+
+/// Parse words e.g. `X1 I[2 + 3] P4` whose letter is in `W::LETTERS`, in any order.
+/// Letters match case-insensitively, and fail when given twice.
+pub fn fold_words<'a, W: Words>(input: Input<'a>) -> IParseResult<'a, W> {
+    fold_many0_result(
+        pair(
+            space_before(consumed(satisfy(|c| {
+                W::LETTERS.contains(c.to_ascii_uppercase())
+            }))),
+            space_before(Expression::parse),
+        ),
+        W::default,
+        |mut words, ((at, letter), value)| {
+            match words.word(letter.to_ascii_uppercase()) {
+                Some(word) if word.is_none() => *word = Some(value),
+                _ => return err(Error::new(at, ErrorKind::Verify)),
+            }
+            ok(words)
+        },
+    )(input)
 }
 
 #[inline(always)]
@@ -2,7 +2,7 @@ extern crate std;
 
 use crate::{
     eval::{Eval as _, EvalContext},
-    gcode::{expression::Expression, Axes, Command},
+    gcode::{expression::Expression, Axes, Block, Command},
     parser::test::{permute_whitespace, ExprBuilder, Param},
     GcodeParseError,
 };
@@ -26,14 +26,14 @@ macro_rules! test_parser_impl {
                 let (rest, actual) = match $node_type::parse(input) {
                     Ok((rest, actual)) => (rest, actual),
                     Err(nom::Err::Error(GcodeParseError::NomError(err))) => {
-                        panic!(
+                        core::panic!(
                             "[input `{}`] [code {:?}] [rest: `{}`]",
                             input,
                             err.code,
                             err.input.as_utf8().unwrap()
                         )
                     }
-                    Err(err) => panic!("{:?}", err),
+                    Err(err) => core::panic!("{:?}", err),
                 };
                 assert_eq!(
                     expected.clone(),
@@ -129,4 +129,5 @@ impl EvalContext for TestContext {
 test_parser_impl!(test_parse_axes, Axes);
 test_parser_impl!(test_parse_param, Param);
 test_parser_impl!(test_parse_command, Command);
+test_parser_impl!(test_parse_block, Block);
 test_parser_impl!(test_parse_expr, Expression);
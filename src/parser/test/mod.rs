@@ -4,6 +4,7 @@ extern crate std;
 mod macro_test_parser;
 mod test_number_code;
 mod test_parse_axes;
+mod test_parse_block;
 mod test_parse_command;
 mod test_parse_expression;
 mod test_parse_param;
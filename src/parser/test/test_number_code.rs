@@ -1,3 +1,4 @@
+extern crate std;
 use crate::parser::{parse_utils::number_code, test::TestContext, Input};
 use core::fmt::Debug;
 
@@ -27,3 +28,48 @@ fn test_parse_number_code() {
     let result = number_code("1")(Input::new(b"1 ", &context));
     assert_same_input(Ok((b" ", b"1")), result);
 }
+
+#[rstest::rstest]
+#[case("0", "0", Some(""))]
+#[case("0", "00", None)]
+#[case("0", "0 X1", Some(" X1"))]
+#[case("1", "1X1", Some("X1"))]
+#[case("1", "10", None)]
+#[case("1", "1.5", None)]
+#[case("1", "1.", None)]
+#[case("38.2", "38.2.", None)]
+#[case("38", "38", Some(""))]
+#[case("38", "38.2", None)]
+#[case("38.2", "38.2", Some(""))]
+#[case("38.2", "38.2 Z-5", Some(" Z-5"))]
+#[case("38.2", "38.3", None)]
+#[case("38.2", "38.25", None)]
+#[case("38.2", "38", None)]
+#[case("33", "33.1", None)]
+#[case("33.1", "33.1K1", Some("K1"))]
+#[case("59", "59.1", None)]
+#[case("59.1", "59.1", Some(""))]
+#[case("59.1", "59.10", None)]
+#[case("64", "64 P0.01", Some(" P0.01"))]
+#[case("64", "64.1", None)]
+#[case("92.1", "92.1", Some(""))]
+#[case("92.1", "92", None)]
+fn test_number_code_matrix(
+    #[case] number: &'static str,
+    #[case] input: &str,
+    #[case] expected_rest: Option<&str>,
+) {
+    let context = TestContext::default().const_fold(false);
+    let result = number_code(number)(Input::new(input.as_bytes(), &context));
+    match (expected_rest, result) {
+        (Some(expected_rest), Ok((rest, matched))) => {
+            assert_eq!(expected_rest.as_bytes(), rest);
+            assert_eq!(number.as_bytes(), matched);
+        }
+        (None, Err(_)) => {}
+        (expected, result) => panic!(
+            "number_code({:?}) on {:?}: expected rest {:?} but got {:?}",
+            number, input, expected, result
+        ),
+    }
+}
@@ -0,0 +1,355 @@
+extern crate std;
+
+use crate::{
+    gcode::*,
+    parser::{test::TestContext, Input},
+    GcodeParser as _,
+};
+use std::prelude::v1::*;
+
+test_parse_block!(empty, [""], |_| Block::default());
+
+test_parse_block!(single_word, ["G0"], |_| Block::new(
+    [Gcode::G0(None).into()]
+));
+
+test_parse_block!(
+    cam_line,
+    ["G1", "X10", "Y5", "F300", "S12000", "M3", "(cut)"],
+    |b| {
+        Block::new([
+            Gcode::G1(
+                Axes::new()
+                    .set(Axis::X, b.lit(10.0))
+                    .set(Axis::Y, b.lit(5.0)),
+            )
+            .into(),
+            Fcode(b.lit(300.0)).into(),
+            Scode(b.lit(12000.0)).into(),
+            Mcode::M3.into(),
+            Command::Comment("cut".to_string()),
+        ])
+    }
+);
+
+test_parse_block!(modal_groups, ["G21", "G90", "G0", "Z5"], |b| {
+    Block::new([
+        Gcode::G21.into(),
+        Gcode::G90.into(),
+        Gcode::G0(Some(Axes::new().set(Axis::Z, b.lit(5.0)))).into(),
+    ])
+});
+
+test_parse_block!(tool_change, ["T1", "M6"], |b| {
+    Block::new([Tcode(b.lit(1.0)).into(), Mcode::M6(None).into()])
+});
+
+test_parse_block!(split_axes, ["G1", "X10", "F300", "Y5"], |b| {
+    Block::new([
+        Gcode::G1(
+            Axes::new()
+                .set(Axis::X, b.lit(10.0))
+                .set(Axis::Y, b.lit(5.0)),
+        )
+        .into(),
+        Fcode(b.lit(300.0)).into(),
+    ])
+});
+
+test_parse_block!(feed_before_axes, ["G1", "F300", "X10"], |b| {
+    Block::new([
+        Gcode::G1(Axes::new().set(Axis::X, b.lit(10.0))).into(),
+        Fcode(b.lit(300.0)).into(),
+    ])
+});
+
+test_parse_block!(axes_before_code, ["X10", "Y5", "G1"], |b| {
+    Block::new([Gcode::G1(
+        Axes::new()
+            .set(Axis::X, b.lit(10.0))
+            .set(Axis::Y, b.lit(5.0)),
+    )
+    .into()])
+});
+
+test_parse_block!(
+    cam_line_reordered,
+    ["S12000", "M3", "(cut)", "F300", "Y5", "G1", "X10"],
+    |b| {
+        Block::new([
+            Scode(b.lit(12000.0)).into(),
+            Mcode::M3.into(),
+            Command::Comment("cut".to_string()),
+            Fcode(b.lit(300.0)).into(),
+            Gcode::G1(
+                Axes::new()
+                    .set(Axis::X, b.lit(10.0))
+                    .set(Axis::Y, b.lit(5.0)),
+            )
+            .into(),
+        ])
+    }
+);
+
+test_parse_block!(set_offsets_reordered, ["X1", "P2", "G10", "L2"], |b| {
+    Block::new([Gcode::G10(
+        SetOffsets::new()
+            .l(b.lit(2.0))
+            .p(b.lit(2.0))
+            .axis(Axis::X, b.lit(1.0)),
+    )
+    .into()])
+});
+
+test_parse_block!(surface_speed, ["G96", "D2500", "S200"], |b| {
+    Block::new([
+        Gcode::G96(Some(b.lit(2500.0))).into(),
+        Scode(b.lit(200.0)).into(),
+    ])
+});
+
+test_parse_block!(surface_speed_max_last, ["G96", "S200", "D2500"], |b| {
+    Block::new([
+        Gcode::G96(Some(b.lit(2500.0))).into(),
+        Scode(b.lit(200.0)).into(),
+    ])
+});
+
+test_parse_block!(dwell_after_feed, ["G4", "F100", "P2"], |b| {
+    Block::new([
+        Gcode::G4(Some(b.lit(2.0))).into(),
+        Fcode(b.lit(100.0)).into(),
+    ])
+});
+
+test_parse_block!(split_arc, ["G2", "X1", "M3", "R1", "Y1"], |b| {
+    Block::new([
+        Gcode::G2(
+            Arc::new()
+                .axis(Axis::X, b.lit(1.0))
+                .axis(Axis::Y, b.lit(1.0))
+                .radius(b.lit(1.0)),
+        )
+        .into(),
+        Mcode::M3.into(),
+    ])
+});
+
+test_parse_block!(assignments, ["#1", "=", "2", "#<x>", "=", "3"], |b| {
+    Block::new([
+        Command::assign(b.num_param(1), b.lit(2.0)),
+        Command::assign(b.local_param("x"), b.lit(3.0)),
+    ])
+});
+
+test_parse_block!(
+    feed_expr,
+    ["G1", "X1", "F", "[", "100", "*", "2", "]"],
+    |b| {
+        Block::new([
+            Gcode::G1(Axes::new().set(Axis::X, b.lit(1.0))).into(),
+            Fcode(b.binop(b.lit(100.0), "*", b.lit(2.0))).into(),
+        ])
+    }
+);
+
+#[test]
+fn test_parse_block_requires_whole_line() {
+    let context = TestContext::default();
+    let result = Block::parse(Input::new(b"G0 X1 Q5", &context));
+    assert!(result.is_err(), "{:?}", result);
+}
+
+#[test]
+fn test_parse_block_call_arguments() {
+    let context = TestContext::default();
+    let arguments = "[1] ".repeat(30);
+    let line = std::format!("O100 call {arguments}");
+    let result = Block::parse(Input::new(line.as_bytes(), &context));
+    assert!(result.is_ok(), "{:?}", result);
+    // #1 to #30
+    let line = std::format!("O100 call {arguments}[31]");
+    let result = Block::parse(Input::new(line.as_bytes(), &context));
+    assert!(result.is_err(), "{:?}", result);
+}
+
+#[test]
+fn test_parse_block_repeated_word() {
+    let context = TestContext::default();
+    for line in [
+        "G1 X1 X2",
+        "G2 X1 R1 r2",
+        "G3 X1 I1 i2",
+        "G10 L2 P1 X1 x2",
+        "G43.2 H1 h2",
+        "G81 Z-1 R1 r2",
+        "G64 P1 p2",
+        "G33 Z1 K1 k2",
+        "G76 P1 P2",
+        "G5 I1 J1 P1 Q1 X1 q2",
+        "G5.2 X1 Y1 P1 L3 l3",
+        "G1 X1 F300 X2",
+        "X1 M3 X2",
+    ] {
+        let result = Block::parse(Input::new(line.as_bytes(), &context));
+        assert!(result.is_err(), "{line} {:?}", result);
+    }
+}
+
+#[test]
+fn test_parse_block_rotary_axes() {
+    // only X, Y and Z are supported
+    let context = TestContext::default();
+    for line in ["G2 X1 A1 R1", "G10 L2 P1 A5", "G0 A1", "B2", "G81 X1 C1 R2"] {
+        let result = Block::parse(Input::new(line.as_bytes(), &context));
+        assert!(result.is_err(), "{line} {:?}", result);
+    }
+}
+
+#[test]
+fn test_parse_block_requires_axes() {
+    let context = TestContext::default();
+    for line in ["G92", "G38.2 F10", "G43.1 M3"] {
+        let result = Block::parse(Input::new(line.as_bytes(), &context));
+        assert!(result.is_err(), "{line} {:?}", result);
+    }
+}
+
+#[test]
+fn test_parse_block_modal_group_conflict() {
+    let context = TestContext::default();
+    for line in [
+        "G0 G1 X1",
+        "G20 G21",
+        "G90 G91",
+        "G17 G18",
+        "M3 M4",
+        "M7 M9",
+        "G4 P1 G53",
+    ] {
+        let result = Block::parse(Input::new(line.as_bytes(), &context));
+        assert!(result.is_err(), "{line} {:?}", result);
+    }
+    for line in ["G0 G90 G21 G17 X1 M3 M8", "M7 M8"] {
+        let result = Block::parse(Input::new(line.as_bytes(), &context));
+        assert!(result.is_ok(), "{line} {:?}", result);
+    }
+}
+
+#[test]
+fn test_parse_block_dwell_requires_p() {
+    let context = TestContext::default();
+    let result = Block::parse(Input::new(b"G4", &context));
+    assert!(result.is_err(), "{:?}", result);
+}
+
+#[test]
+fn test_parse_block_comp_diameter_requires_d() {
+    let context = TestContext::default();
+    for line in ["G41.1", "G42.1 F100", "G41 D1 D2", "M3 D1"] {
+        let result = Block::parse(Input::new(line.as_bytes(), &context));
+        assert!(result.is_err(), "{line} {:?}", result);
+    }
+}
+
+#[test]
+fn test_parse_block_trailing_cr() {
+    let context = TestContext::default();
+    let (_, block) = Block::parse(Input::new(b"G20\r", &context)).unwrap();
+    assert_eq!(block, Block::new([Gcode::G20.into()]));
+}
+
+test_parse_block!(line_number, ["N10", "G0"], |_| {
+    Block::new([Gcode::G0(None).into()]).line_number(10)
+});
+
+test_parse_block!(line_number_lower, ["n", "20", "G21"], |_| {
+    Block::new([Gcode::G21.into()]).line_number(20)
+});
+
+test_parse_block!(line_number_only, ["N30"], |_| Block::default()
+    .line_number(30));
+
+test_parse_block!(block_delete, ["/", "G0"], |_| {
+    Block::new([Gcode::G0(None).into()]).block_delete(true)
+});
+
+test_parse_block!(block_delete_line_number, ["/", "N10", "M3"], |_| {
+    Block::new([Mcode::M3.into()])
+        .block_delete(true)
+        .line_number(10)
+});
+
+test_parse_block!(program_delimiter, ["%"], |_| {
+    Block::new([Command::ProgramDelimiter])
+});
+
+test_parse_block!(program_delimiter_comment, ["%", "(start)"], |_| {
+    Block::new([
+        Command::ProgramDelimiter,
+        Command::Comment("start".to_string()),
+    ])
+});
+
+#[test]
+fn test_parse_block_program_delimiter_only_comments() {
+    let context = TestContext::default();
+    let result = Block::parse(Input::new(b"% G0", &context));
+    assert!(result.is_err(), "{:?}", result);
+}
+
+test_parse_block!(line_comment, ["G0", "X1", ";", "rapid (to start)"], |b| {
+    Block::new([
+        Gcode::G0(Some(Axes::new().set(Axis::X, b.lit(1.0)))).into(),
+        Command::LineComment("rapid (to start)".to_string()),
+    ])
+});
+
+test_parse_block!(
+    paren_and_line_comment,
+    ["M3", "(spindle)", ";", "on"],
+    |_| {
+        Block::new([
+            Mcode::M3.into(),
+            Command::Comment("spindle".to_string()),
+            Command::LineComment("on".to_string()),
+        ])
+    }
+);
+
+test_parse_block!(nurbs_control_point, ["X2", "Y0", "P1"], |b| {
+    Block::new([Command::ControlPoint(
+        NurbsPoint::new()
+            .axis(Axis::X, b.lit(2.0))
+            .axis(Axis::Y, b.lit(0.0))
+            .p(b.lit(1.0)),
+    )])
+});
+
+test_parse_block!(modal_axes, ["X2", "Y0"], |b| {
+    Block::new([Command::Axes(
+        Axes::new()
+            .set(Axis::X, b.lit(2.0))
+            .set(Axis::Y, b.lit(0.0)),
+    )])
+});
+
+test_parse_block!(program_delimiter_line_comment, ["%", ";", "start"], |_| {
+    Block::new([
+        Command::ProgramDelimiter,
+        Command::LineComment("start".to_string()),
+    ])
+});
+
+#[test]
+fn test_parse_block_line_comment_crlf() {
+    let context = TestContext::default();
+    let (_, block) = Block::parse(Input::new(b"G21 ; metric\r\n", &context)).unwrap();
+    assert_eq!(
+        block,
+        Block::new([
+            Gcode::G21.into(),
+            Command::LineComment("metric".to_string())
+        ])
+    );
+}
@@ -1,6 +1,7 @@
 extern crate std;
 
 use crate::gcode::*;
+use std::prelude::v1::*;
 
 use super::TestContext;
 
@@ -24,12 +25,255 @@ test_parse_command!(g1, ["G1", "X1"], |b| {
     Gcode::G1(Axes::new().set(Axis::X, b.lit(1.0)))
 });
 
+test_parse_command!(g2_ijk, ["G2", "X10", "Y0", "I5", "J0"], |b| {
+    Gcode::G2(
+        Arc::new()
+            .axis(Axis::X, b.lit(10.0))
+            .axis(Axis::Y, b.lit(0.0))
+            .offset(Axis::X, b.lit(5.0))
+            .offset(Axis::Y, b.lit(0.0)),
+    )
+});
+
+test_parse_command!(g2_any_order, ["G2", "j0", "X10", "i5"], |b| {
+    Gcode::G2(
+        Arc::new()
+            .axis(Axis::X, b.lit(10.0))
+            .offset(Axis::X, b.lit(5.0))
+            .offset(Axis::Y, b.lit(0.0)),
+    )
+});
+
+test_parse_command!(
+    g3_radius,
+    ["G3", "X0", "Y10", "R", "[", "5", "*", "2", "]"],
+    |b| {
+        Gcode::G3(
+            Arc::new()
+                .axis(Axis::X, b.lit(0.0))
+                .axis(Axis::Y, b.lit(10.0))
+                .radius(b.binop(b.lit(5.0), "*", b.lit(2.0))),
+        )
+    }
+);
+
+test_parse_command!(g3_helical_turns, ["G3", "Z-1", "I5", "P2"], |b| {
+    Gcode::G3(
+        Arc::new()
+            .axis(Axis::Z, b.lit(-1.0))
+            .offset(Axis::X, b.lit(5.0))
+            .turns(b.lit(2.0)),
+    )
+});
+
+test_parse_command!(g2_mixed_radius_offsets, ["G2", "X10", "R5", "I5"], |b| {
+    Gcode::G2(
+        Arc::new()
+            .axis(Axis::X, b.lit(10.0))
+            .radius(b.lit(5.0))
+            .offset(Axis::X, b.lit(5.0)),
+    )
+});
+
+test_parse_command!(g4, ["G4", "P1.5"], |b| Gcode::G4(Some(b.lit(1.5))));
+test_parse_command!(g4_expr, ["G4", "p", "[", "1", "+", "2", "]"], |b| {
+    Gcode::G4(Some(b.binop(b.lit(1.0), "+", b.lit(2.0))))
+});
+
+test_parse_command!(g10_l2, ["G10", "L2", "P1", "X1", "Y-2"], |b| Gcode::G10(
+    SetOffsets::new()
+        .l(b.lit(2.0))
+        .p(b.lit(1.0))
+        .axis(Axis::X, b.lit(1.0))
+        .axis(Axis::Y, b.lit(-2.0))
+));
+test_parse_command!(g10_l20_any_order, ["G10", "X0", "P0", "L20"], |b| {
+    Gcode::G10(
+        SetOffsets::new()
+            .l(b.lit(20.0))
+            .p(b.lit(0.0))
+            .axis(Axis::X, b.lit(0.0)),
+    )
+});
+test_parse_command!(
+    g10_l1,
+    ["G10", "L1", "P3", "Z-1.5", "R0.25", "I10", "J20", "Q2"],
+    |b| Gcode::G10(
+        SetOffsets::new()
+            .l(b.lit(1.0))
+            .p(b.lit(3.0))
+            .axis(Axis::Z, b.lit(-1.5))
+            .r(b.lit(0.25))
+            .i(b.lit(10.0))
+            .j(b.lit(20.0))
+            .q(b.lit(2.0))
+    )
+);
+test_parse_command!(g17, ["G17"], |_| Gcode::G17);
+test_parse_command!(g17_1, ["G17.1"], |_| Gcode::G17_1);
+test_parse_command!(g18, ["G18"], |_| Gcode::G18);
+test_parse_command!(g18_1, ["G18.1"], |_| Gcode::G18_1);
+test_parse_command!(g19, ["G19"], |_| Gcode::G19);
+test_parse_command!(g19_1, ["G19.1"], |_| Gcode::G19_1);
 test_parse_command!(g20, ["G20"], |_| Gcode::G20);
 test_parse_command!(g21, ["G21"], |_| Gcode::G21);
+test_parse_command!(g28, ["G28"], |_| Gcode::G28(None));
+test_parse_command!(g28_z1, ["G28", "Z1"], |b| Gcode::G28(Some(
+    Axes::new().set(Axis::Z, b.lit(1.0))
+)));
+test_parse_command!(g28_1, ["G28.1"], |_| Gcode::G28_1);
+test_parse_command!(g30, ["G30"], |_| Gcode::G30(None));
+test_parse_command!(g30_1, ["G30.1"], |_| Gcode::G30_1);
+test_parse_command!(g38_2, ["G38.2", "Z-10"], |b| Gcode::G38_2(
+    Axes::new().set(Axis::Z, b.lit(-10.0))
+));
+test_parse_command!(g38_3, ["G38.3", "X1", "Y1"], |b| Gcode::G38_3(
+    Axes::new()
+        .set(Axis::X, b.lit(1.0))
+        .set(Axis::Y, b.lit(1.0))
+));
+test_parse_command!(g38_4, ["G38.4", "Z1"], |b| Gcode::G38_4(
+    Axes::new().set(Axis::Z, b.lit(1.0))
+));
+test_parse_command!(g38_5, ["G38.5", "Z1"], |b| Gcode::G38_5(
+    Axes::new().set(Axis::Z, b.lit(1.0))
+));
+test_parse_command!(g5, ["G5", "X10", "Y0", "I0", "J5", "P0", "Q5"], |b| {
+    Gcode::G5(
+        Spline::new()
+            .axis(Axis::X, b.lit(10.0))
+            .axis(Axis::Y, b.lit(0.0))
+            .i(b.lit(0.0))
+            .j(b.lit(5.0))
+            .p(b.lit(0.0))
+            .q(b.lit(5.0)),
+    )
+});
+test_parse_command!(g5_1, ["G5.1", "X10", "I5", "J5"], |b| Gcode::G5_1(
+    Spline::new()
+        .axis(Axis::X, b.lit(10.0))
+        .i(b.lit(5.0))
+        .j(b.lit(5.0))
+));
+test_parse_command!(g5_2, ["G5.2", "X10", "Y10", "P0.5", "L3"], |b| {
+    Gcode::G5_2(
+        NurbsPoint::new()
+            .axis(Axis::X, b.lit(10.0))
+            .axis(Axis::Y, b.lit(10.0))
+            .p(b.lit(0.5))
+            .l(b.lit(3.0)),
+    )
+});
+test_parse_command!(g5_3, ["G5.3"], |_| Gcode::G5_3);
+test_parse_command!(g7, ["G7"], |_| Gcode::G7);
+test_parse_command!(g8, ["G8"], |_| Gcode::G8);
+test_parse_command!(g33, ["G33", "Z-10", "K1.5"], |b| Gcode::G33(
+    SyncedMotion::new()
+        .axis(Axis::Z, b.lit(-10.0))
+        .k(b.lit(1.5))
+));
+test_parse_command!(g33_1, ["G33.1", "Z-5", "K1"], |b| Gcode::G33_1(
+    SyncedMotion::new().axis(Axis::Z, b.lit(-5.0)).k(b.lit(1.0))
+));
+test_parse_command!(
+    g76,
+    ["G76", "P1.5", "Z-20", "I-0.5", "J0.2", "R2", "K1", "Q29.5", "H2", "E1", "L3"],
+    |b| Gcode::G76(
+        Threading::new()
+            .p(b.lit(1.5))
+            .z(b.lit(-20.0))
+            .i(b.lit(-0.5))
+            .j(b.lit(0.2))
+            .r(b.lit(2.0))
+            .k(b.lit(1.0))
+            .q(b.lit(29.5))
+            .h(b.lit(2.0))
+            .e(b.lit(1.0))
+            .l(b.lit(3.0))
+    )
+);
+test_parse_command!(g40, ["G40"], |_| Gcode::G40);
+test_parse_command!(g41, ["G41"], |_| Gcode::G41(None));
+test_parse_command!(g41_d, ["G41", "D3"], |b| Gcode::G41(Some(b.lit(3.0))));
+test_parse_command!(g41_1, ["G41.1", "D0.25"], |b| Gcode::G41_1(Some(
+    b.lit(0.25)
+)));
+test_parse_command!(g42, ["G42", "D1"], |b| Gcode::G42(Some(b.lit(1.0))));
+test_parse_command!(g42_1, ["G42.1", "D", "[", "#1", "]"], |b| Gcode::G42_1(
+    Some(b.num_param_expr(1))
+));
+test_parse_command!(g43, ["G43"], |_| Gcode::G43(None));
+test_parse_command!(g43_h, ["G43", "H2"], |b| Gcode::G43(Some(b.lit(2.0))));
+test_parse_command!(g43_1, ["G43.1", "Z0.5"], |b| Gcode::G43_1(
+    Axes::new().set(Axis::Z, b.lit(0.5))
+));
+test_parse_command!(g43_2, ["G43.2", "H4", "Z0.1"], |b| Gcode::G43_2(
+    ToolOffset::new().h(b.lit(4.0)).axis(Axis::Z, b.lit(0.1))
+));
+test_parse_command!(g49, ["G49"], |_| Gcode::G49);
 test_parse_command!(g53, ["G53"], |_| Gcode::G53);
 test_parse_command!(g54, ["G54"], |_| Gcode::G54);
+test_parse_command!(g55, ["G55"], |_| Gcode::G55);
+test_parse_command!(g59, ["G59"], |_| Gcode::G59);
+test_parse_command!(g59_1, ["G59.1"], |_| Gcode::G59_1);
+test_parse_command!(g59_3, ["G59.3"], |_| Gcode::G59_3);
+test_parse_command!(g61, ["G61"], |_| Gcode::G61);
+test_parse_command!(g61_1, ["G61.1"], |_| Gcode::G61_1);
+test_parse_command!(g64, ["G64"], |_| Gcode::G64(Blending::new()));
+test_parse_command!(g64_p_q, ["G64", "P0.01", "Q0.005"], |b| Gcode::G64(
+    Blending::new().p(b.lit(0.01)).q(b.lit(0.005))
+));
+test_parse_command!(g80, ["G80"], |_| Gcode::G80);
+test_parse_command!(g81, ["G81", "X1", "Y2", "Z-3", "R1", "L2"], |b| {
+    Gcode::G81(
+        CannedCycle::new()
+            .axis(Axis::X, b.lit(1.0))
+            .axis(Axis::Y, b.lit(2.0))
+            .axis(Axis::Z, b.lit(-3.0))
+            .r(b.lit(1.0))
+            .l(b.lit(2.0)),
+    )
+});
+test_parse_command!(g83, ["G83", "Z-3", "R1", "Q0.5"], |b| Gcode::G83(
+    CannedCycle::new()
+        .axis(Axis::Z, b.lit(-3.0))
+        .r(b.lit(1.0))
+        .q(b.lit(0.5))
+));
+test_parse_command!(g87, ["G87", "Z-3", "R1", "I2", "J0", "K-1", "P0"], |b| {
+    Gcode::G87(
+        CannedCycle::new()
+            .axis(Axis::Z, b.lit(-3.0))
+            .r(b.lit(1.0))
+            .offset(Axis::X, b.lit(2.0))
+            .offset(Axis::Y, b.lit(0.0))
+            .offset(Axis::Z, b.lit(-1.0))
+            .p(b.lit(0.0)),
+    )
+});
 test_parse_command!(g90, ["G90"], |_| Gcode::G90);
 test_parse_command!(g91, ["G91"], |_| Gcode::G91);
+test_parse_command!(g92, ["G92", "X0", "Y1"], |b| Gcode::G92(
+    Axes::new()
+        .set(Axis::X, b.lit(0.0))
+        .set(Axis::Y, b.lit(1.0))
+));
+test_parse_command!(g92_1, ["G92.1"], |_| Gcode::G92_1);
+test_parse_command!(g93, ["G93"], |_| Gcode::G93);
+test_parse_command!(g94, ["G94"], |_| Gcode::G94);
+test_parse_command!(g95, ["G95"], |_| Gcode::G95);
+test_parse_command!(g96, ["G96", "D2500"], |b| Gcode::G96(Some(b.lit(2500.0))));
+test_parse_command!(g96_no_d, ["G96"], |_| Gcode::G96(None));
+test_parse_command!(g97, ["G97"], |_| Gcode::G97);
+test_parse_command!(g98, ["G98"], |_| Gcode::G98);
+test_parse_command!(g99, ["G99"], |_| Gcode::G99);
+test_parse_command!(axes, ["X1", "Y2"], |b| Command::Axes(
+    Axes::new()
+        .set(Axis::X, b.lit(1.0))
+        .set(Axis::Y, b.lit(2.0))
+));
+test_parse_command!(g92_2, ["G92.2"], |_| Gcode::G92_2);
+test_parse_command!(g92_3, ["G92.3"], |_| Gcode::G92_3);
 
 test_parse_command!(o100_if, ["O100", "if", "1"], |b| Ocode::new(
     100,
@@ -51,6 +295,97 @@ test_parse_command!(o100_sub, ["o100", "sub"], |_| Ocode::new(
     OcodeStatement::Sub
 ));
 
+test_parse_command!(o100_return, ["o100", "return"], |_| Ocode::new(
+    100,
+    OcodeStatement::Return(None)
+));
+
+test_parse_command!(o100_return_value, ["o100", "return", "[#1 * 2]"], |b| {
+    Ocode::new(
+        100,
+        OcodeStatement::Return(Some(b.binop(b.num_param_expr(1), "*", b.lit(2.0)))),
+    )
+});
+
+test_parse_command!(o100_endsub, ["O100", "endsub"], |_| Ocode::new(
+    100,
+    OcodeStatement::EndSub(None)
+));
+
+test_parse_command!(o100_endsub_value, ["O100", "endsub", "[-1]"], |b| {
+    Ocode::new(100, OcodeStatement::EndSub(Some(b.lit(-1.0))))
+});
+
+test_parse_command!(o100_call, ["O100", "call"], |_| Ocode::new(
+    100,
+    OcodeStatement::Call(Vec::new())
+));
+
+test_parse_command!(
+    o100_call_arguments,
+    ["O100", "call", "[1.5]", "[#2]", "[", "3", "+", "4", "]"],
+    |b| Ocode::new(
+        100,
+        OcodeStatement::Call(vec![
+            b.lit(1.5),
+            b.num_param_expr(2),
+            b.binop(b.lit(3.0), "+", b.lit(4.0)),
+        ])
+    )
+);
+
+test_parse_command!(o100_elseif, ["O100", "elseif", "[#1 GT 2]"], |b| {
+    Ocode::new(
+        100,
+        OcodeStatement::ElseIf(b.binop(b.num_param_expr(1), "GT", b.lit(2.0))),
+    )
+});
+
+test_parse_command!(o100_else, ["O100", "else"], |_| Ocode::new(
+    100,
+    OcodeStatement::Else
+));
+
+test_parse_command!(o101_while, ["O101", "while", "[#1 LT 3]"], |b| {
+    Ocode::new(
+        101,
+        OcodeStatement::While(b.binop(b.num_param_expr(1), "LT", b.lit(3.0))),
+    )
+});
+
+test_parse_command!(o101_endwhile, ["O101", "endwhile"], |_| Ocode::new(
+    101,
+    OcodeStatement::EndWhile
+));
+
+test_parse_command!(o102_do, ["O102", "do"], |_| Ocode::new(
+    102,
+    OcodeStatement::Do
+));
+
+test_parse_command!(o103_repeat, ["O103", "repeat", "[5]"], |b| Ocode::new(
+    103,
+    OcodeStatement::Repeat(b.lit(5.0))
+));
+
+test_parse_command!(o103_endrepeat, ["O103", "ENDREPEAT"], |_| Ocode::new(
+    103,
+    OcodeStatement::EndRepeat
+));
+
+test_parse_command!(o101_break, ["O101", "break"], |_| Ocode::new(
+    101,
+    OcodeStatement::Break
+));
+
+test_parse_command!(o101_continue, ["O101", "continue"], |_| Ocode::new(
+    101,
+    OcodeStatement::Continue
+));
+
+test_parse_command!(m0, ["M0"], |_| Mcode::M0);
+test_parse_command!(m1, ["M1"], |_| Mcode::M1);
+test_parse_command!(m2, ["M2"], |_| Mcode::M2);
 test_parse_command!(m3, ["M3"], |_| Mcode::M3);
 test_parse_command!(m4, ["M4"], |_| Mcode::M4);
 test_parse_command!(m5, ["M5"], |_| Mcode::M5);
@@ -59,10 +394,23 @@ test_parse_command!(m6_t6, ["M6", "T8"], |b| Mcode::M6(Some(Tcode(b.lit(8.0)))))
 test_parse_command!(m7, ["M7"], |_| Mcode::M7);
 test_parse_command!(m8, ["M8"], |_| Mcode::M8);
 test_parse_command!(m9, ["M9"], |_| Mcode::M9);
+test_parse_command!(m30, ["M30"], |_| Mcode::M30);
+test_parse_command!(m60, ["M60"], |_| Mcode::M60);
 
+test_parse_command!(f300, ["F300"], |b| Fcode(b.lit(300.0)));
 test_parse_command!(s1000, ["S1000"], |b| Scode(b.lit(1000.0)));
 test_parse_command!(t1, ["T1"], |b| Tcode(b.lit(1.0)));
 
+test_parse_command!(comment, ["(cut)"], |_| {
+    Command::Comment("cut".to_string())
+});
+test_parse_command!(line_comment, [";", "cut here"], |_| {
+    Command::LineComment("cut here".to_string())
+});
+test_parse_command!(line_comment_empty, [";"], |_| {
+    Command::LineComment("".to_string())
+});
+
 test_parse_command!(assign, ["#1", "=", "1"], |b| {
     Command::assign(b.num_param(1), b.lit(1.0))
 });
@@ -21,9 +21,9 @@ fn test_parse_atom(#[case] input: &str, #[case] expected: Expression) {
     let parsed = match Expression::parse(input) {
         Ok((_, parsed)) => parsed,
         Err(nom::Err::Error(GcodeParseError::NomError(Error { input, code }))) => {
-            panic!("{:?} {}", code, input.as_utf8().unwrap())
+            core::panic!("{:?} {}", code, input.as_utf8().unwrap())
         }
-        Err(err) => panic!("{:?}", err),
+        Err(err) => core::panic!("{:?}", err),
     };
     assert_eq!(parsed, expected);
 }
@@ -1,14 +1,24 @@
+mod parse_arc;
 mod parse_assignment;
 mod parse_axes;
+mod parse_blending;
+mod parse_block;
+mod parse_canned_cycle;
 mod parse_command;
 mod parse_comment;
 mod parse_expression;
+mod parse_fcode;
 mod parse_gcode;
 mod parse_mcode;
 mod parse_ocode;
 mod parse_param;
 mod parse_scode;
+mod parse_set_offsets;
+mod parse_spline;
+mod parse_synced_motion;
 mod parse_tcode;
+mod parse_threading;
+mod parse_tool_offset;
 
 pub use parse_assignment::*;
 pub use parse_comment::*;
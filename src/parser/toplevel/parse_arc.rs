@@ -0,0 +1,26 @@
+use crate::{
+    gcode::{expression::Expression, Arc, Axis},
+    parser::{fold_words, nom_types::IParseResult, Input, Words},
+    GcodeParser,
+};
+
+impl GcodeParser for Arc {
+    fn parse(input: Input) -> IParseResult<Self> {
+        fold_words(input)
+    }
+}
+
+impl Words for Arc {
+    const LETTERS: &'static str = "XYZABCIJKRP";
+
+    fn word(&mut self, letter: char) -> Option<&mut Option<Expression>> {
+        match letter {
+            'I' => self.offsets.word_mut(Axis::X),
+            'J' => self.offsets.word_mut(Axis::Y),
+            'K' => self.offsets.word_mut(Axis::Z),
+            'R' => Some(&mut self.radius),
+            'P' => Some(&mut self.turns),
+            _ => self.axes.word(letter),
+        }
+    }
+}
@@ -1,9 +1,13 @@
 use crate::{
     gcode::{expression::Expression, Axes, Axis},
-    parser::{nom_types::IParseResult, parse_utils::space_before, Input},
+    parser::{fold_words, nom_types::IParseResult, parse_utils::space_before, Input, Words},
     GcodeParser,
 };
-use nom::{character::complete::one_of, combinator::map_res, multi::fold_many1, sequence::pair};
+use nom::{
+    character::complete::one_of,
+    combinator::{map_res, verify},
+    sequence::pair,
+};
 
 impl GcodeParser for Axes {
     fn parse(input: Input) -> IParseResult<Self> {
@@ -17,12 +21,17 @@ impl GcodeParser for (Axis, Expression) {
     }
 }
 
+impl Words for Axes {
+    const LETTERS: &'static str = "XYZABC";
+
+    fn word(&mut self, letter: char) -> Option<&mut Option<Expression>> {
+        self.word_mut(Axis::from_chr(letter)?)
+    }
+}
+
+/// At least one axis word
 fn parse_axes(input: Input) -> IParseResult<Axes> {
-    fold_many1(
-        <(Axis, Expression)>::parse,
-        Axes::default,
-        |axes, (axis, expr)| axes.set(axis, expr),
-    )(input)
+    verify(fold_words, |axes: &Axes| !axes.is_empty())(input)
 }
 
 fn parse_axis(input: Input) -> IParseResult<(Axis, Expression)> {
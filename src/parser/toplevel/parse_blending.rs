@@ -0,0 +1,23 @@
+use crate::{
+    gcode::{expression::Expression, Blending},
+    parser::{fold_words, nom_types::IParseResult, Input, Words},
+    GcodeParser,
+};
+
+impl GcodeParser for Blending {
+    fn parse(input: Input) -> IParseResult<Self> {
+        fold_words(input)
+    }
+}
+
+impl Words for Blending {
+    const LETTERS: &'static str = "PQ";
+
+    fn word(&mut self, letter: char) -> Option<&mut Option<Expression>> {
+        match letter {
+            'P' => Some(&mut self.p),
+            'Q' => Some(&mut self.q),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,333 @@
+use crate::{
+    gcode::{expression::Expression, Axes, Block, Command, Gcode, Mcode, NurbsPoint},
+    parser::{
+        nom_types::IParseResult, ok, parse_u32, space_before, toplevel::parse_comment, Input, Words,
+    },
+    GcodeParser,
+};
+use alloc::vec::Vec;
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, tag_no_case},
+    character::complete::{multispace0, satisfy},
+    combinator::{eof, map_res, opt},
+    multi::fold_many0,
+    sequence::{pair, preceded, terminated, tuple},
+};
+
+impl GcodeParser for Block {
+    fn parse(input: Input) -> IParseResult<Self> {
+        // every word on the line must be consumed, up to optional trailing whitespace
+        terminated(
+            alt((parse_program_delimiter, parse_words)),
+            tuple((multispace0, eof)),
+        )(input)
+    }
+}
+
+/// `%` line, which may only be followed by comments
+fn parse_program_delimiter(input: Input) -> IParseResult<Block> {
+    map_res(
+        preceded(space_before(tag("%")), collect_commands(parse_comment)),
+        |comments| {
+            let mut commands = Vec::with_capacity(comments.len() + 1);
+            commands.push(Command::ProgramDelimiter);
+            commands.extend(comments);
+            ok(Block::new(commands))
+        },
+    )(input)
+}
+
+/// `[/][N123] words...`
+fn parse_words(input: Input) -> IParseResult<Block> {
+    map_res(
+        tuple((
+            opt(space_before(tag("/"))),
+            opt(preceded(
+                space_before(tag_no_case("N")),
+                space_before(parse_u32()),
+            )),
+            collect_items,
+        )),
+        |(block_delete, line_number, items)| {
+            Ok::<_, ()>(Block {
+                block_delete: block_delete.is_some(),
+                line_number,
+                commands: merge_words(items)?,
+            })
+        },
+    )(input)
+}
+
+/// Letters of the words which follow a code
+const WORD_LETTERS: &str = "XYZABCDEHIJKLPQR";
+
+/// A command, or a word which does not directly follow its code, e.g. the `Y5` of
+/// `G1 X10 F300 Y5`
+#[allow(clippy::large_enum_variant)]
+enum Item {
+    Command(Command),
+    Word(char, Expression),
+}
+
+fn collect_items(input: Input) -> IParseResult<Vec<Item>> {
+    fold_many0(
+        alt((
+            map_res(
+                pair(
+                    space_before(satisfy(|c| WORD_LETTERS.contains(c.to_ascii_uppercase()))),
+                    space_before(Expression::parse),
+                ),
+                |(letter, value)| ok(Item::Word(letter.to_ascii_uppercase(), value)),
+            ),
+            map_res(Command::parse, |command| ok(Item::Command(command))),
+        )),
+        Vec::new,
+        |mut items, item| {
+            items.push(item);
+            items
+        },
+    )(input)
+}
+
+/// Gives each word to the first code of the block which takes it. Words no code takes
+/// are axis words repeating the motion mode, or a NURBS control point.
+fn merge_words(items: Vec<Item>) -> Result<Vec<Command>, ()> {
+    let mut commands = Vec::with_capacity(items.len());
+    let mut words = Vec::new();
+    for item in items {
+        match item {
+            Item::Command(command) => commands.push(command),
+            Item::Word(letter, value) => words.push((letter, value)),
+        }
+    }
+    if modal_conflict(&commands) {
+        return Err(());
+    }
+    let mut rest = Vec::new();
+    for (letter, value) in words {
+        match commands
+            .iter_mut()
+            .find_map(|command| code_word(command, letter))
+        {
+            Some(word) if word.is_none() => *word = Some(value),
+            Some(_) => return Err(()),
+            None => rest.push((letter, value)),
+        }
+    }
+    if commands.iter().any(missing_words) {
+        return Err(());
+    }
+    if rest.is_empty() {
+        return Ok(commands);
+    }
+    if let Some(axes) = collect_words::<Axes>(&rest) {
+        commands.push(Command::Axes(axes));
+    } else {
+        let point = collect_words::<NurbsPoint>(&rest).filter(|point| point.p.is_some());
+        commands.push(Command::ControlPoint(point.ok_or(())?));
+    }
+    Ok(commands)
+}
+
+/// Groups of codes of which a block may hold one each, as in LinuxCNC
+#[derive(PartialEq, Clone, Copy)]
+enum ModalGroup {
+    NonModal,
+    Motion,
+    Plane,
+    Distance,
+    FeedMode,
+    Units,
+    CutterComp,
+    ToolLength,
+    RetractMode,
+    CoordinateSystem,
+    PathControl,
+    SpindleMode,
+    DiameterMode,
+    Stop,
+    ToolChange,
+    Spindle,
+    Coolant,
+}
+
+fn modal_group(command: &Command) -> Option<ModalGroup> {
+    let group = match command {
+        Command::G(gcode) => match gcode {
+            Gcode::G4(_)
+            | Gcode::G10(_)
+            | Gcode::G28(_)
+            | Gcode::G28_1
+            | Gcode::G30(_)
+            | Gcode::G30_1
+            | Gcode::G53
+            | Gcode::G92(_)
+            | Gcode::G92_1
+            | Gcode::G92_2
+            | Gcode::G92_3 => ModalGroup::NonModal,
+            Gcode::G0(_)
+            | Gcode::G1(_)
+            | Gcode::G2(_)
+            | Gcode::G3(_)
+            | Gcode::G5(_)
+            | Gcode::G5_1(_)
+            | Gcode::G5_2(_)
+            | Gcode::G5_3
+            | Gcode::G33(_)
+            | Gcode::G33_1(_)
+            | Gcode::G38_2(_)
+            | Gcode::G38_3(_)
+            | Gcode::G38_4(_)
+            | Gcode::G38_5(_)
+            | Gcode::G73(_)
+            | Gcode::G74(_)
+            | Gcode::G76(_)
+            | Gcode::G80
+            | Gcode::G81(_)
+            | Gcode::G82(_)
+            | Gcode::G83(_)
+            | Gcode::G84(_)
+            | Gcode::G85(_)
+            | Gcode::G86(_)
+            | Gcode::G87(_)
+            | Gcode::G88(_)
+            | Gcode::G89(_) => ModalGroup::Motion,
+            Gcode::G17 | Gcode::G17_1 | Gcode::G18 | Gcode::G18_1 | Gcode::G19 | Gcode::G19_1 => {
+                ModalGroup::Plane
+            }
+            Gcode::G90 | Gcode::G91 => ModalGroup::Distance,
+            Gcode::G93 | Gcode::G94 | Gcode::G95 => ModalGroup::FeedMode,
+            Gcode::G20 | Gcode::G21 => ModalGroup::Units,
+            Gcode::G40 | Gcode::G41(_) | Gcode::G41_1(_) | Gcode::G42(_) | Gcode::G42_1(_) => {
+                ModalGroup::CutterComp
+            }
+            Gcode::G43(_) | Gcode::G43_1(_) | Gcode::G43_2(_) | Gcode::G49 => {
+                ModalGroup::ToolLength
+            }
+            Gcode::G98 | Gcode::G99 => ModalGroup::RetractMode,
+            Gcode::G54
+            | Gcode::G55
+            | Gcode::G56
+            | Gcode::G57
+            | Gcode::G58
+            | Gcode::G59
+            | Gcode::G59_1
+            | Gcode::G59_2
+            | Gcode::G59_3 => ModalGroup::CoordinateSystem,
+            Gcode::G61 | Gcode::G61_1 | Gcode::G64(_) => ModalGroup::PathControl,
+            Gcode::G96(_) | Gcode::G97 => ModalGroup::SpindleMode,
+            Gcode::G7 | Gcode::G8 => ModalGroup::DiameterMode,
+        },
+        Command::M(mcode) => match mcode {
+            Mcode::M0 | Mcode::M1 | Mcode::M2 | Mcode::M30 | Mcode::M60 => ModalGroup::Stop,
+            Mcode::M6(_) => ModalGroup::ToolChange,
+            Mcode::M3 | Mcode::M4 | Mcode::M5 => ModalGroup::Spindle,
+            Mcode::M7 | Mcode::M8 | Mcode::M9 => ModalGroup::Coolant,
+        },
+        _ => return None,
+    };
+    Some(group)
+}
+
+/// Two codes of the same modal group in a block, e.g. `G0 G1`. Mist and flood coolant
+/// may be turned on together.
+fn modal_conflict(commands: &[Command]) -> bool {
+    commands.iter().enumerate().any(|(i, command)| {
+        let Some(group) = modal_group(command) else {
+            return false;
+        };
+        commands[i + 1..].iter().any(|other| {
+            modal_group(other) == Some(group)
+                && !matches!(
+                    (command, other),
+                    (Command::M(Mcode::M7), Command::M(Mcode::M8))
+                        | (Command::M(Mcode::M8), Command::M(Mcode::M7))
+                )
+        })
+    })
+}
+
+/// The words of a code which takes `letter`
+fn code_word(command: &mut Command, letter: char) -> Option<&mut Option<Expression>> {
+    let Command::G(gcode) = command else {
+        return None;
+    };
+    match gcode {
+        Gcode::G0(axes) | Gcode::G28(axes) | Gcode::G30(axes) if Axes::LETTERS.contains(letter) => {
+            axes.get_or_insert_with(Axes::new).word(letter)
+        }
+        Gcode::G1(axes)
+        | Gcode::G38_2(axes)
+        | Gcode::G38_3(axes)
+        | Gcode::G38_4(axes)
+        | Gcode::G38_5(axes)
+        | Gcode::G43_1(axes)
+        | Gcode::G92(axes) => axes.word(letter),
+        Gcode::G4(p) if letter == 'P' => Some(p),
+        Gcode::G41(d) | Gcode::G41_1(d) | Gcode::G42(d) | Gcode::G42_1(d) | Gcode::G96(d)
+            if letter == 'D' =>
+        {
+            Some(d)
+        }
+        Gcode::G43(h) if letter == 'H' => Some(h),
+        Gcode::G2(arc) | Gcode::G3(arc) => arc.word(letter),
+        Gcode::G5(spline) | Gcode::G5_1(spline) => spline.word(letter),
+        Gcode::G5_2(point) => point.word(letter),
+        Gcode::G10(set) => set.word(letter),
+        Gcode::G33(motion) | Gcode::G33_1(motion) => motion.word(letter),
+        Gcode::G43_2(offset) => offset.word(letter),
+        Gcode::G64(blending) => blending.word(letter),
+        Gcode::G73(cycle)
+        | Gcode::G74(cycle)
+        | Gcode::G81(cycle)
+        | Gcode::G82(cycle)
+        | Gcode::G83(cycle)
+        | Gcode::G84(cycle)
+        | Gcode::G85(cycle)
+        | Gcode::G86(cycle)
+        | Gcode::G87(cycle)
+        | Gcode::G88(cycle)
+        | Gcode::G89(cycle) => cycle.word(letter),
+        Gcode::G76(threading) => threading.word(letter),
+        _ => None,
+    }
+}
+
+/// Code without the words it needs, which may be anywhere in its block
+fn missing_words(command: &Command) -> bool {
+    match command {
+        Command::G(Gcode::G4(word) | Gcode::G41_1(word) | Gcode::G42_1(word)) => word.is_none(),
+        Command::G(
+            Gcode::G38_2(axes)
+            | Gcode::G38_3(axes)
+            | Gcode::G38_4(axes)
+            | Gcode::G38_5(axes)
+            | Gcode::G43_1(axes)
+            | Gcode::G92(axes),
+        ) => axes.is_empty(),
+        _ => false,
+    }
+}
+
+/// `words` as the words of a `W`, if it takes all of them
+fn collect_words<W: Words>(words: &[(char, Expression)]) -> Option<W> {
+    let mut collected = W::default();
+    for (letter, value) in words {
+        let word = collected.word(*letter)?;
+        if word.is_some() {
+            return None;
+        }
+        *word = Some(value.clone());
+    }
+    Some(collected)
+}
+
+fn collect_commands<'a>(
+    parser: impl FnMut(Input<'a>) -> IParseResult<'a, Command>,
+) -> impl FnMut(Input<'a>) -> IParseResult<'a, Vec<Command>> {
+    fold_many0(parser, Vec::new, |mut commands, command| {
+        commands.push(command);
+        commands
+    })
+}
@@ -0,0 +1,28 @@
+use crate::{
+    gcode::{expression::Expression, Axis, CannedCycle},
+    parser::{fold_words, nom_types::IParseResult, Input, Words},
+    GcodeParser,
+};
+
+impl GcodeParser for CannedCycle {
+    fn parse(input: Input) -> IParseResult<Self> {
+        fold_words(input)
+    }
+}
+
+impl Words for CannedCycle {
+    const LETTERS: &'static str = "XYZABCRQPLIJK";
+
+    fn word(&mut self, letter: char) -> Option<&mut Option<Expression>> {
+        match letter {
+            'R' => Some(&mut self.r),
+            'Q' => Some(&mut self.q),
+            'P' => Some(&mut self.p),
+            'L' => Some(&mut self.l),
+            'I' => self.offsets.word_mut(Axis::X),
+            'J' => self.offsets.word_mut(Axis::Y),
+            'K' => self.offsets.word_mut(Axis::Z),
+            _ => self.axes.word(letter),
+        }
+    }
+}
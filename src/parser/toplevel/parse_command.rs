@@ -1,12 +1,12 @@
 use crate::{
-    gcode::{Command, Gcode, Mcode, Ocode, Scode, Tcode},
+    gcode::{Axes, Command, Fcode, Gcode, Mcode, NurbsPoint, Ocode, Scode, Tcode},
     parser::{nom_types::IParseResult, ok, parse_utils::space_before, toplevel::*, Input},
     GcodeParser,
 };
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    combinator::{map_res, peek},
+    combinator::{map_res, peek, verify},
     sequence::preceded,
 };
 
@@ -27,8 +27,17 @@ impl GcodeParser for Command {
             command(Command::G, Gcode::parse),
             command(Command::M, Mcode::parse),
             command(Command::O, Ocode::parse),
+            command(Command::F, Fcode::parse),
             command(Command::S, Scode::parse),
             command(Command::T, Tcode::parse),
+            command(Command::ControlPoint, parse_control_point),
+            command(Command::Axes, Axes::parse),
         )))(input)
     }
 }
+
+/// NURBS control point words, which need their `P` weight to tell them apart from
+/// axis words
+fn parse_control_point(input: Input) -> IParseResult<NurbsPoint> {
+    verify(NurbsPoint::parse, |point: &NurbsPoint| point.p.is_some())(input)
+}
@@ -4,12 +4,18 @@ use crate::{
 };
 use alloc::string::String;
 use nom::{
-    bytes::complete::{tag, take_until1},
+    branch::alt,
+    bytes::complete::{tag, take_till, take_until1},
     combinator::map_res,
-    sequence::delimited,
+    sequence::{delimited, preceded},
 };
 
 pub fn parse_comment(input: Input) -> IParseResult<Command> {
+    alt((parse_paren_comment, parse_line_comment))(input)
+}
+
+/// `( comment )`
+fn parse_paren_comment(input: Input) -> IParseResult<Command> {
     map_res(
         delimited(space_before(tag("(")), take_until1(")"), tag(")")),
         move |input| {
@@ -18,3 +24,17 @@ pub fn parse_comment(input: Input) -> IParseResult<Command> {
         },
     )(input)
 }
+
+/// `; comment` to the end of the line, surrounding whitespace is trimmed
+fn parse_line_comment(input: Input) -> IParseResult<Command> {
+    map_res(
+        preceded(
+            space_before(tag(";")),
+            take_till(|b| b == b'\r' || b == b'\n'),
+        ),
+        move |input: Input| {
+            let comment_str = input.as_utf8()?.trim();
+            ok(Command::LineComment(String::from(comment_str)))
+        },
+    )(input)
+}
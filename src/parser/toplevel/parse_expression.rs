@@ -12,10 +12,9 @@ use alloc::boxed::Box;
 use nom::{
     branch::alt,
     bytes::complete::{tag, tag_no_case},
-    character::complete::alpha1,
-    combinator::{map_res, not},
+    character::complete::{alpha1, char, digit0, digit1, one_of},
+    combinator::{map_opt, map_res, not, opt, recognize},
     error::{Error, ErrorKind},
-    number::complete::float,
     sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
     Parser as _,
 };
@@ -69,10 +68,24 @@ fn parse_atom(input: Input) -> IParseResult<'_, Expression> {
             Expression::param(param)
         }),
         // number literal e.g. `1.0`
-        map_res_f1(float, Expression::lit),
+        map_res_f1(parse_number, Expression::lit),
     )))(input)
 }
 
+/// Decimal number without an exponent, `E` is the word of `G76`
+fn parse_number(input: Input) -> IParseResult<'_, f32> {
+    map_opt(
+        recognize(pair(
+            opt(one_of("+-")),
+            alt((
+                recognize(pair(digit1, opt(pair(char('.'), digit0)))),
+                recognize(pair(char('.'), digit1)),
+            )),
+        )),
+        |number: Input| number.as_utf8().ok()?.parse().ok(),
+    )(input)
+}
+
 fn parse_func_call(input: Input) -> IParseResult<'_, Expression> {
     alt((
         parse_func_call_atan,
@@ -0,0 +1,16 @@
+use crate::{
+    gcode::{expression::Expression, Fcode},
+    parser::{map_res_f1, nom_types::IParseResult, space_before, Input},
+    GcodeParser,
+};
+use nom::{bytes::complete::tag_no_case, sequence::preceded, Parser as _};
+
+impl GcodeParser for Fcode {
+    fn parse(input: Input) -> IParseResult<Self> {
+        preceded(
+            space_before(tag_no_case("F")),
+            map_res_f1(Expression::parse, Fcode),
+        )
+        .parse(input)
+    }
+}
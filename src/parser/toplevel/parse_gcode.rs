@@ -1,30 +1,261 @@
 use crate::{
-    gcode::{Axes, Gcode},
+    gcode::{
+        expression::Expression, Arc, Axes, Blending, CannedCycle, Gcode, NurbsPoint, SetOffsets,
+        Spline, SyncedMotion, Threading, ToolOffset,
+    },
     parser::{
-        map_res_f1,
+        fold_words, map_res_f1,
         nom_types::{IParseResult, IntoParser as _},
         parse_code_and_number::parse_code_and_number,
-        Input,
+        space_before, Input,
     },
     GcodeParser,
 };
-use nom::{combinator::opt, Parser as _};
+use nom::{
+    branch::alt, bytes::complete::tag_no_case, combinator::opt, sequence::preceded, Parser as _,
+};
 
 impl GcodeParser for Gcode {
     fn parse(input: Input) -> IParseResult<Self> {
-        parse_code_and_number(
-            b'G',
+        // split by modal group, each code list is limited in length
+        alt((
+            parse_motion,
+            parse_non_modal,
+            parse_plane,
+            parse_units,
+            parse_diameter_mode,
+            parse_cutter_comp,
+            parse_tool_length,
+            parse_coordinate_system,
+            parse_path_control,
+            parse_distance,
+            parse_canned_cycle,
+            parse_feed_mode,
+            parse_spindle_mode,
+            parse_retract_mode,
+        ))(input)
+    }
+}
+
+fn parse_motion(input: Input) -> IParseResult<Gcode> {
+    parse_code_and_number(
+        b'G',
+        (
+            ("0", map_res_f1(opt(Axes::parse), Gcode::G0)),
+            ("1", map_res_f1(fold_words, Gcode::G1)),
+            ("2", map_res_f1(Arc::parse, Gcode::G2)),
+            ("3", map_res_f1(Arc::parse, Gcode::G3)),
+            ("5", map_res_f1(Spline::parse, Gcode::G5)),
+            ("5.1", map_res_f1(Spline::parse, Gcode::G5_1)),
+            ("5.2", map_res_f1(NurbsPoint::parse, Gcode::G5_2)),
+            ("5.3", Gcode::G5_3.into_parser()),
+            ("33", map_res_f1(SyncedMotion::parse, Gcode::G33)),
+            ("33.1", map_res_f1(SyncedMotion::parse, Gcode::G33_1)),
+            ("38.2", map_res_f1(fold_words, Gcode::G38_2)),
+            ("38.3", map_res_f1(fold_words, Gcode::G38_3)),
+            ("38.4", map_res_f1(fold_words, Gcode::G38_4)),
+            ("38.5", map_res_f1(fold_words, Gcode::G38_5)),
+            ("76", map_res_f1(Threading::parse, Gcode::G76)),
+        ),
+    )
+    .parse(input)
+}
+
+fn parse_non_modal(input: Input) -> IParseResult<Gcode> {
+    parse_code_and_number(
+        b'G',
+        (
             (
-                ("0", map_res_f1(opt(Axes::parse), Gcode::G0)),
-                ("1", map_res_f1(Axes::parse, Gcode::G1)),
-                ("20", Gcode::G20.into_parser()),
-                ("21", Gcode::G21.into_parser()),
-                ("53", Gcode::G53.into_parser()),
-                ("54", Gcode::G54.into_parser()),
-                ("90", Gcode::G90.into_parser()),
-                ("91", Gcode::G91.into_parser()),
+                "4",
+                map_res_f1(
+                    opt(preceded(space_before(tag_no_case("P")), Expression::parse)),
+                    Gcode::G4,
+                ),
             ),
-        )
-        .parse(input)
-    }
+            ("10", map_res_f1(SetOffsets::parse, Gcode::G10)),
+            ("28", map_res_f1(opt(Axes::parse), Gcode::G28)),
+            ("28.1", Gcode::G28_1.into_parser()),
+            ("30", map_res_f1(opt(Axes::parse), Gcode::G30)),
+            ("30.1", Gcode::G30_1.into_parser()),
+            ("53", Gcode::G53.into_parser()),
+            ("92", map_res_f1(fold_words, Gcode::G92)),
+            ("92.1", Gcode::G92_1.into_parser()),
+            ("92.2", Gcode::G92_2.into_parser()),
+            ("92.3", Gcode::G92_3.into_parser()),
+        ),
+    )
+    .parse(input)
+}
+
+fn parse_plane(input: Input) -> IParseResult<Gcode> {
+    parse_code_and_number(
+        b'G',
+        (
+            ("17", Gcode::G17.into_parser()),
+            ("17.1", Gcode::G17_1.into_parser()),
+            ("18", Gcode::G18.into_parser()),
+            ("18.1", Gcode::G18_1.into_parser()),
+            ("19", Gcode::G19.into_parser()),
+            ("19.1", Gcode::G19_1.into_parser()),
+        ),
+    )
+    .parse(input)
+}
+
+fn parse_units(input: Input) -> IParseResult<Gcode> {
+    parse_code_and_number(
+        b'G',
+        (
+            ("20", Gcode::G20.into_parser()),
+            ("21", Gcode::G21.into_parser()),
+        ),
+    )
+    .parse(input)
+}
+
+fn parse_diameter_mode(input: Input) -> IParseResult<Gcode> {
+    parse_code_and_number(
+        b'G',
+        (
+            ("7", Gcode::G7.into_parser()),
+            ("8", Gcode::G8.into_parser()),
+        ),
+    )
+    .parse(input)
+}
+
+fn parse_cutter_comp(input: Input) -> IParseResult<Gcode> {
+    let d = || preceded(space_before(tag_no_case("D")), Expression::parse);
+    parse_code_and_number(
+        b'G',
+        (
+            ("40", Gcode::G40.into_parser()),
+            ("41", map_res_f1(opt(d()), Gcode::G41)),
+            ("41.1", map_res_f1(opt(d()), Gcode::G41_1)),
+            ("42", map_res_f1(opt(d()), Gcode::G42)),
+            ("42.1", map_res_f1(opt(d()), Gcode::G42_1)),
+        ),
+    )
+    .parse(input)
+}
+
+fn parse_tool_length(input: Input) -> IParseResult<Gcode> {
+    parse_code_and_number(
+        b'G',
+        (
+            (
+                "43",
+                map_res_f1(
+                    opt(preceded(space_before(tag_no_case("H")), Expression::parse)),
+                    Gcode::G43,
+                ),
+            ),
+            ("43.1", map_res_f1(fold_words, Gcode::G43_1)),
+            ("43.2", map_res_f1(ToolOffset::parse, Gcode::G43_2)),
+            ("49", Gcode::G49.into_parser()),
+        ),
+    )
+    .parse(input)
+}
+
+fn parse_coordinate_system(input: Input) -> IParseResult<Gcode> {
+    parse_code_and_number(
+        b'G',
+        (
+            ("54", Gcode::G54.into_parser()),
+            ("55", Gcode::G55.into_parser()),
+            ("56", Gcode::G56.into_parser()),
+            ("57", Gcode::G57.into_parser()),
+            ("58", Gcode::G58.into_parser()),
+            ("59", Gcode::G59.into_parser()),
+            ("59.1", Gcode::G59_1.into_parser()),
+            ("59.2", Gcode::G59_2.into_parser()),
+            ("59.3", Gcode::G59_3.into_parser()),
+        ),
+    )
+    .parse(input)
+}
+
+fn parse_path_control(input: Input) -> IParseResult<Gcode> {
+    parse_code_and_number(
+        b'G',
+        (
+            ("61", Gcode::G61.into_parser()),
+            ("61.1", Gcode::G61_1.into_parser()),
+            ("64", map_res_f1(Blending::parse, Gcode::G64)),
+        ),
+    )
+    .parse(input)
+}
+
+fn parse_distance(input: Input) -> IParseResult<Gcode> {
+    parse_code_and_number(
+        b'G',
+        (
+            ("90", Gcode::G90.into_parser()),
+            ("91", Gcode::G91.into_parser()),
+        ),
+    )
+    .parse(input)
+}
+
+fn parse_canned_cycle(input: Input) -> IParseResult<Gcode> {
+    parse_code_and_number(
+        b'G',
+        (
+            ("73", map_res_f1(CannedCycle::parse, Gcode::G73)),
+            ("74", map_res_f1(CannedCycle::parse, Gcode::G74)),
+            ("80", Gcode::G80.into_parser()),
+            ("81", map_res_f1(CannedCycle::parse, Gcode::G81)),
+            ("82", map_res_f1(CannedCycle::parse, Gcode::G82)),
+            ("83", map_res_f1(CannedCycle::parse, Gcode::G83)),
+            ("84", map_res_f1(CannedCycle::parse, Gcode::G84)),
+            ("85", map_res_f1(CannedCycle::parse, Gcode::G85)),
+            ("86", map_res_f1(CannedCycle::parse, Gcode::G86)),
+            ("87", map_res_f1(CannedCycle::parse, Gcode::G87)),
+            ("88", map_res_f1(CannedCycle::parse, Gcode::G88)),
+            ("89", map_res_f1(CannedCycle::parse, Gcode::G89)),
+        ),
+    )
+    .parse(input)
+}
+
+fn parse_feed_mode(input: Input) -> IParseResult<Gcode> {
+    parse_code_and_number(
+        b'G',
+        (
+            ("93", Gcode::G93.into_parser()),
+            ("94", Gcode::G94.into_parser()),
+            ("95", Gcode::G95.into_parser()),
+        ),
+    )
+    .parse(input)
+}
+
+fn parse_spindle_mode(input: Input) -> IParseResult<Gcode> {
+    parse_code_and_number(
+        b'G',
+        (
+            (
+                "96",
+                map_res_f1(
+                    opt(preceded(space_before(tag_no_case("D")), Expression::parse)),
+                    Gcode::G96,
+                ),
+            ),
+            ("97", Gcode::G97.into_parser()),
+        ),
+    )
+    .parse(input)
+}
+
+fn parse_retract_mode(input: Input) -> IParseResult<Gcode> {
+    parse_code_and_number(
+        b'G',
+        (
+            ("98", Gcode::G98.into_parser()),
+            ("99", Gcode::G99.into_parser()),
+        ),
+    )
+    .parse(input)
 }
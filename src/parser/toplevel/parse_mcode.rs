@@ -19,6 +19,9 @@ impl GcodeParser for Mcode {
         parse_code_and_number(
             b'M',
             (
+                ("0", Mcode::M0.into_parser()),
+                ("1", Mcode::M1.into_parser()),
+                ("2", Mcode::M2.into_parser()),
                 ("3", Mcode::M3.into_parser()),
                 ("4", Mcode::M4.into_parser()),
                 ("5", Mcode::M5.into_parser()),
@@ -29,6 +32,8 @@ impl GcodeParser for Mcode {
                 ("7", Mcode::M7.into_parser()),
                 ("8", Mcode::M8.into_parser()),
                 ("9", Mcode::M9.into_parser()),
+                ("30", Mcode::M30.into_parser()),
+                ("60", Mcode::M60.into_parser()),
             ),
         )
         .parse(input)
@@ -9,14 +9,19 @@ use crate::{
     },
     GcodeParser,
 };
+use alloc::vec::Vec;
 use nom::{
     branch::alt,
-    bytes::complete::tag_no_case,
-    combinator::map_res,
-    sequence::{preceded, tuple},
+    bytes::complete::{tag, tag_no_case},
+    combinator::{map_res, opt, verify},
+    multi::fold_many0,
+    sequence::{delimited, preceded, tuple},
     Parser as _,
 };
 
+/// Arguments of a subroutine call, `#1` to `#30`
+const MAX_ARGUMENTS: usize = 30;
+
 impl GcodeParser for Ocode {
     fn parse(input: Input) -> IParseResult<Self> {
         preceded(space_before(tag_no_case("O")), parse_ocode).parse(input)
@@ -29,14 +34,63 @@ fn parse_ocode(input: Input) -> IParseResult<Ocode> {
             parse_u32(),
             space_before(alt((
                 map_res(tag_no_case("sub"), |_| ok(OcodeStatement::Sub)),
-                map_res(tag_no_case("endsub"), |_| ok(OcodeStatement::EndSub)),
+                preceded(
+                    tag_no_case("endsub"),
+                    map_res_f1(opt(Expression::parse), OcodeStatement::EndSub),
+                ),
+                preceded(
+                    tag_no_case("return"),
+                    map_res_f1(opt(Expression::parse), OcodeStatement::Return),
+                ),
+                preceded(
+                    tag_no_case("call"),
+                    map_res_f1(parse_arguments, OcodeStatement::Call),
+                ),
                 preceded(
                     tag_no_case("if"),
                     map_res_f1(Expression::parse, OcodeStatement::If),
                 ),
+                // before `else`, which is its prefix
+                preceded(
+                    tag_no_case("elseif"),
+                    map_res_f1(Expression::parse, OcodeStatement::ElseIf),
+                ),
+                map_res(tag_no_case("else"), |_| ok(OcodeStatement::Else)),
                 map_res(tag_no_case("endif"), |_| ok(OcodeStatement::EndIf)),
+                preceded(
+                    tag_no_case("while"),
+                    map_res_f1(Expression::parse, OcodeStatement::While),
+                ),
+                map_res(tag_no_case("endwhile"), |_| ok(OcodeStatement::EndWhile)),
+                map_res(tag_no_case("do"), |_| ok(OcodeStatement::Do)),
+                preceded(
+                    tag_no_case("repeat"),
+                    map_res_f1(Expression::parse, OcodeStatement::Repeat),
+                ),
+                map_res(tag_no_case("endrepeat"), |_| ok(OcodeStatement::EndRepeat)),
+                map_res(tag_no_case("break"), |_| ok(OcodeStatement::Break)),
+                map_res(tag_no_case("continue"), |_| ok(OcodeStatement::Continue)),
             ))),
         )),
         |(id, stmt)| ok(Ocode::new(id, stmt)),
     )(input)
 }
+
+/// `[1.5] [#2] ...`, every argument in brackets
+fn parse_arguments(input: Input) -> IParseResult<Vec<Expression>> {
+    verify(
+        fold_many0(
+            delimited(
+                space_before(tag("[")),
+                Expression::parse,
+                space_before(tag("]")),
+            ),
+            Vec::new,
+            |mut arguments, argument| {
+                arguments.push(argument);
+                arguments
+            },
+        ),
+        |arguments: &Vec<Expression>| arguments.len() <= MAX_ARGUMENTS,
+    )(input)
+}
@@ -0,0 +1,27 @@
+use crate::{
+    gcode::{expression::Expression, SetOffsets},
+    parser::{fold_words, nom_types::IParseResult, Input, Words},
+    GcodeParser,
+};
+
+impl GcodeParser for SetOffsets {
+    fn parse(input: Input) -> IParseResult<Self> {
+        fold_words(input)
+    }
+}
+
+impl Words for SetOffsets {
+    const LETTERS: &'static str = "XYZABCLPRIJQ";
+
+    fn word(&mut self, letter: char) -> Option<&mut Option<Expression>> {
+        match letter {
+            'L' => Some(&mut self.l),
+            'P' => Some(&mut self.p),
+            'R' => Some(&mut self.r),
+            'I' => Some(&mut self.i),
+            'J' => Some(&mut self.j),
+            'Q' => Some(&mut self.q),
+            _ => self.axes.word(letter),
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{
+    gcode::{expression::Expression, NurbsPoint, Spline},
+    parser::{fold_words, nom_types::IParseResult, Input, Words},
+    GcodeParser,
+};
+
+impl GcodeParser for Spline {
+    fn parse(input: Input) -> IParseResult<Self> {
+        fold_words(input)
+    }
+}
+
+impl Words for Spline {
+    const LETTERS: &'static str = "XYIJPQ";
+
+    fn word(&mut self, letter: char) -> Option<&mut Option<Expression>> {
+        match letter {
+            'I' => Some(&mut self.i),
+            'J' => Some(&mut self.j),
+            'P' => Some(&mut self.p),
+            'Q' => Some(&mut self.q),
+            _ => self.axes.word(letter),
+        }
+    }
+}
+
+impl GcodeParser for NurbsPoint {
+    fn parse(input: Input) -> IParseResult<Self> {
+        fold_words(input)
+    }
+}
+
+impl Words for NurbsPoint {
+    const LETTERS: &'static str = "XYPL";
+
+    fn word(&mut self, letter: char) -> Option<&mut Option<Expression>> {
+        match letter {
+            'P' => Some(&mut self.p),
+            'L' => Some(&mut self.l),
+            _ => self.axes.word(letter),
+        }
+    }
+}
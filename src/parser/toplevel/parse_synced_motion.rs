@@ -0,0 +1,22 @@
+use crate::{
+    gcode::{expression::Expression, SyncedMotion},
+    parser::{fold_words, nom_types::IParseResult, Input, Words},
+    GcodeParser,
+};
+
+impl GcodeParser for SyncedMotion {
+    fn parse(input: Input) -> IParseResult<Self> {
+        fold_words(input)
+    }
+}
+
+impl Words for SyncedMotion {
+    const LETTERS: &'static str = "XYZABCK";
+
+    fn word(&mut self, letter: char) -> Option<&mut Option<Expression>> {
+        match letter {
+            'K' => Some(&mut self.k),
+            _ => self.axes.word(letter),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{
+    gcode::{expression::Expression, Threading},
+    parser::{fold_words, nom_types::IParseResult, Input, Words},
+    GcodeParser,
+};
+
+impl GcodeParser for Threading {
+    fn parse(input: Input) -> IParseResult<Self> {
+        fold_words(input)
+    }
+}
+
+impl Words for Threading {
+    const LETTERS: &'static str = "ZPIJRKQHEL";
+
+    fn word(&mut self, letter: char) -> Option<&mut Option<Expression>> {
+        match letter {
+            'Z' => Some(&mut self.z),
+            'P' => Some(&mut self.p),
+            'I' => Some(&mut self.i),
+            'J' => Some(&mut self.j),
+            'R' => Some(&mut self.r),
+            'K' => Some(&mut self.k),
+            'Q' => Some(&mut self.q),
+            'H' => Some(&mut self.h),
+            'E' => Some(&mut self.e),
+            'L' => Some(&mut self.l),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use crate::{
+    gcode::{expression::Expression, ToolOffset},
+    parser::{fold_words, nom_types::IParseResult, Input, Words},
+    GcodeParser,
+};
+
+impl GcodeParser for ToolOffset {
+    fn parse(input: Input) -> IParseResult<Self> {
+        fold_words(input)
+    }
+}
+
+impl Words for ToolOffset {
+    const LETTERS: &'static str = "XYZABCH";
+
+    fn word(&mut self, letter: char) -> Option<&mut Option<Expression>> {
+        match letter {
+            'H' => Some(&mut self.h),
+            _ => self.axes.word(letter),
+        }
+    }
+}
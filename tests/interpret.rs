@@ -0,0 +1,51 @@
+use embedded_gcode::{
+    ArcMove, InterpretValue, Interpreter, MmSec, MotionEvent, Move, MoveKind, Program,
+};
+
+fn interpret(interpreter: &mut Interpreter, line: &[u8]) -> Vec<MotionEvent> {
+    let (_, block) = Program::parse_block(line).unwrap();
+    interpreter
+        .interpret_block(block)
+        .unwrap()
+        .into_iter()
+        .flat_map(|value| match value {
+            InterpretValue::Motion(events) => events,
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+fn single_move(events: Vec<MotionEvent>) -> Move {
+    match <[_; 1]>::try_from(events) {
+        Ok([MotionEvent::Move(mv)]) => mv,
+        other => panic!("expected a single move, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_moves() {
+    let mut interpreter = Interpreter::default();
+
+    let mv = single_move(interpret(&mut interpreter, b"G0 X10 Y5"));
+    assert_eq!(mv.kind, MoveKind::Rapid);
+    assert_eq!((mv.to[0], mv.to[1]), (10.0, 5.0));
+
+    let mv = single_move(interpret(&mut interpreter, b"G1 X20 F600"));
+    assert_eq!(mv.kind, MoveKind::Linear);
+    assert_eq!(mv.feedrate, MmSec(10.0));
+    assert_eq!((mv.to[0], mv.to[1]), (20.0, 5.0));
+
+    let mv = single_move(interpret(&mut interpreter, b"G3 X10 Y15 I-10"));
+    let MoveKind::Arc(ArcMove { center, turns }) = mv.kind else {
+        panic!("expected an arc, got {:?}", mv.kind);
+    };
+    assert_eq!((center[0], center[1]), (10.0, 5.0));
+    assert_eq!(turns, 1);
+    assert_eq!((mv.to[0], mv.to[1]), (10.0, 15.0));
+}
+
+#[test]
+fn test_program_end() {
+    let mut interpreter = Interpreter::default();
+    assert_eq!(interpret(&mut interpreter, b"M2"), [MotionEvent::End]);
+}